@@ -0,0 +1,259 @@
+//! [`StreamAccessibility`], summarising the access services provided by an elementary stream.
+//!
+//! Access services for viewers with impaired sight or hearing may be signalled in a number of
+//! ways: by the `audio_type` of the _ISO 639 Language Descriptor_, by the _Supplementary Audio
+//! Descriptor_, by the `component_type` given in the codec-specific audio descriptors, the
+//! _Subtitling Descriptor_ and the event's _Component Descriptor_.  Broadcasters are not
+//! consistent in which of these they use, so all are taken into account.
+use crate::audio::{
+    AacType, Ac3ComponentType, Ac3ServiceType, AudioType, EditorialClassification, MixType,
+};
+use crate::component::ComponentDescriptor;
+use crate::extension::ExtensionDescriptors;
+use crate::subtitling::SubtitlingType;
+use crate::En300_468Descriptors;
+use mpeg2ts_reader::{descriptor, psi};
+
+/// How an audio description stream is to be presented
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AudioDescriptionMix {
+    /// The description is already mixed with the programme audio, so the stream can be presented
+    /// on its own
+    BroadcastMix,
+    /// The stream carries only the description, which the receiver must mix with the main audio
+    /// stream
+    ReceiverMix,
+    /// The stream carries audio description, but the signalling does not say how it is mixed
+    Unspecified,
+}
+
+/// The access services provided by a single elementary stream.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct StreamAccessibility {
+    /// Audio description for the visually impaired
+    pub audio_description: Option<AudioDescriptionMix>,
+    /// Audio with enhanced dialogue intelligibility, for the hearing impaired
+    pub clean_audio: bool,
+    /// Subtitles rendered as speech, for the visually impaired
+    pub spoken_subtitles: bool,
+    /// Sign language interpretation, for the deaf
+    pub signing: bool,
+}
+impl StreamAccessibility {
+    /// Summarise the access services of the given elementary stream from the PMT, taking account
+    /// of the EIT event's component descriptor for this stream (see
+    /// [`ComponentStreams`](../component/struct.ComponentStreams.html)) if available.
+    pub fn from_stream(
+        stream_info: &psi::pmt::StreamInfo<'_>,
+        component: Option<&ComponentDescriptor<'_>>,
+    ) -> StreamAccessibility {
+        Self::new(
+            stream_info.descriptors::<En300_468Descriptors<'_>>(),
+            component,
+        )
+    }
+
+    /// Summarise the access services signalled by the given elementary stream descriptors, and
+    /// by the stream's component descriptor if available.
+    ///
+    /// Where a _Supplementary Audio Descriptor_ is present, the audio description mix type it
+    /// signals takes precedence over that implied by any `component_type`.
+    pub fn new<'buf>(
+        descriptors: impl IntoIterator<
+            Item = Result<En300_468Descriptors<'buf>, descriptor::DescriptorError>,
+        >,
+        component: Option<&ComponentDescriptor<'_>>,
+    ) -> StreamAccessibility {
+        let mut result = StreamAccessibility::default();
+        if let Some(component) = component {
+            result.add_component(component);
+        }
+        for desc in descriptors.into_iter().flatten() {
+            match desc {
+                En300_468Descriptors::ISO639Language(d) => {
+                    for lang in d.languages() {
+                        match lang.audio_type() {
+                            AudioType::HearingImpaired => result.clean_audio = true,
+                            AudioType::VisualImpairedCommentary => {
+                                result.add_audio_description(AudioDescriptionMix::Unspecified)
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                En300_468Descriptors::AC3(d) => {
+                    if let Some(component_type) = d.component_type() {
+                        result.add_ac3(component_type);
+                    }
+                }
+                En300_468Descriptors::EnhancedAC3(d) => {
+                    if let Some(component_type) = d.component_type() {
+                        result.add_ac3(component_type);
+                    }
+                }
+                En300_468Descriptors::AAC(d) => {
+                    if let Some(aac_type) = d.aac_type() {
+                        result.add_aac(aac_type);
+                    }
+                }
+                En300_468Descriptors::Subtitling(d) => {
+                    for entry in d.entries() {
+                        result.add_subtitling(entry.subtitling_type());
+                    }
+                }
+                En300_468Descriptors::Extension(ext) => {
+                    if let Ok(ExtensionDescriptors::SupplementaryAudio(d)) = ext.descriptor() {
+                        match d.editorial_classification() {
+                            EditorialClassification::AudioDescription => {
+                                result.audio_description = Some(match d.mix_type() {
+                                    MixType::Independent => AudioDescriptionMix::BroadcastMix,
+                                    MixType::Supplementary => AudioDescriptionMix::ReceiverMix,
+                                })
+                            }
+                            EditorialClassification::CleanAudio => result.clean_audio = true,
+                            EditorialClassification::SpokenSubtitles => {
+                                result.spoken_subtitles = true
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        result
+    }
+
+    /// `true` if the stream provides any access service
+    pub fn has_access_services(&self) -> bool {
+        self.audio_description.is_some()
+            || self.clean_audio
+            || self.spoken_subtitles
+            || self.signing
+    }
+
+    /// Record audio description, with a specific mix type replacing `Unspecified`
+    fn add_audio_description(&mut self, mix: AudioDescriptionMix) {
+        if matches!(
+            self.audio_description,
+            None | Some(AudioDescriptionMix::Unspecified)
+        ) {
+            self.audio_description = Some(mix);
+        }
+    }
+
+    fn add_component(&mut self, component: &ComponentDescriptor<'_>) {
+        match (component.stream_content(), component.component_type()) {
+            (0x2, 0x40) => self.add_audio_description(AudioDescriptionMix::Unspecified),
+            (0x2, 0x41) => self.clean_audio = true,
+            (0x2, 0x47) => self.add_audio_description(AudioDescriptionMix::ReceiverMix),
+            (0x2, 0x48) => self.add_audio_description(AudioDescriptionMix::BroadcastMix),
+            (0x3, component_type) => self.add_subtitling(SubtitlingType::from_id(component_type)),
+            (0x4, component_type) => self.add_ac3(Ac3ComponentType(component_type)),
+            (0x6, component_type) => self.add_aac(AacType::from_id(component_type)),
+            _ => (),
+        }
+    }
+
+    fn add_ac3(&mut self, component_type: Ac3ComponentType) {
+        match component_type.service_type() {
+            Ac3ServiceType::VisuallyImpaired => {
+                self.add_audio_description(if component_type.full_service() {
+                    AudioDescriptionMix::BroadcastMix
+                } else {
+                    AudioDescriptionMix::ReceiverMix
+                })
+            }
+            Ac3ServiceType::HearingImpaired => self.clean_audio = true,
+            _ => (),
+        }
+    }
+
+    fn add_aac(&mut self, aac_type: AacType) {
+        match aac_type {
+            AacType::HeAacAudioDescription | AacType::HeAacV2AudioDescription => {
+                self.add_audio_description(AudioDescriptionMix::Unspecified)
+            }
+            AacType::HeAacReceiverMixAudioDescription
+            | AacType::HeAacV2ReceiverMixAudioDescription => {
+                self.add_audio_description(AudioDescriptionMix::ReceiverMix)
+            }
+            AacType::HeAacBroadcastMixAudioDescription
+            | AacType::HeAacV2BroadcastMixAudioDescription => {
+                self.add_audio_description(AudioDescriptionMix::BroadcastMix)
+            }
+            AacType::HeAacHardOfHearing | AacType::HeAacV2HardOfHearing => self.clean_audio = true,
+            _ => (),
+        }
+    }
+
+    fn add_subtitling(&mut self, subtitling_type: SubtitlingType) {
+        if matches!(
+            subtitling_type,
+            SubtitlingType::OpenSignLanguage | SubtitlingType::ClosedSignLanguage
+        ) {
+            self.signing = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn supplementary_audio_takes_precedence() {
+        let component =
+            ComponentDescriptor::new(0x50, &[0x02, 0x48, 0x01, b'e', b'n', b'g']).unwrap();
+        #[rustfmt::skip]
+        let data = [
+            // ISO_639_language_descriptor, visual impaired commentary
+            0x0A, 0x04, b'e', b'n', b'g', 0x03,
+            // supplementary_audio_descriptor, receiver-mix audio description
+            0x7F, 0x02, 0x06, 0b0000_0100,
+        ];
+        let access =
+            StreamAccessibility::new(descriptor::DescriptorIter::new(&data[..]), Some(&component));
+        assert_eq!(
+            Some(AudioDescriptionMix::ReceiverMix),
+            access.audio_description
+        );
+        assert!(!access.clean_audio);
+
+        let access = StreamAccessibility::new(
+            descriptor::DescriptorIter::new(&data[..6]),
+            Some(&component),
+        );
+        assert_eq!(
+            Some(AudioDescriptionMix::BroadcastMix),
+            access.audio_description
+        );
+    }
+
+    #[test]
+    fn stream_accessibility() {
+        #[rustfmt::skip]
+        let data = [
+            // AC-3_descriptor, hearing impaired full service
+            0x6A, 0x02, 0x80, 0x5A,
+            // subtitling_descriptor, closed sign language
+            0x59, 0x08, b'e', b'n', b'g', 0x31, 0x00, 0x01, 0x00, 0x01,
+            // supplementary_audio_descriptor, spoken subtitles
+            0x7F, 0x02, 0x06, 0b1000_1100,
+        ];
+        let access = StreamAccessibility::new(descriptor::DescriptorIter::new(&data[..]), None);
+        assert_eq!(
+            StreamAccessibility {
+                audio_description: None,
+                clean_audio: true,
+                spoken_subtitles: true,
+                signing: true,
+            },
+            access
+        );
+        assert!(access.has_access_services());
+
+        let access = StreamAccessibility::new(descriptor::DescriptorIter::new(&data[..0]), None);
+        assert!(!access.has_access_services());
+    }
+}
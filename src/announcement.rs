@@ -0,0 +1,278 @@
+//! _Announcement Support Descriptor_ (tag 0x6E)
+use crate::ServiceTriplet;
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// The kinds of announcement which a service may support
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AnnouncementType {
+    EmergencyAlarm,
+    RoadTrafficFlash,
+    PublicTransportFlash,
+    WarningMessage,
+    NewsFlash,
+    WeatherFlash,
+    EventAnnouncement,
+    PersonalCall,
+    Reserved(u8),
+}
+impl AnnouncementType {
+    pub fn from_id(id: u8) -> AnnouncementType {
+        match id {
+            0 => AnnouncementType::EmergencyAlarm,
+            1 => AnnouncementType::RoadTrafficFlash,
+            2 => AnnouncementType::PublicTransportFlash,
+            3 => AnnouncementType::WarningMessage,
+            4 => AnnouncementType::NewsFlash,
+            5 => AnnouncementType::WeatherFlash,
+            6 => AnnouncementType::EventAnnouncement,
+            7 => AnnouncementType::PersonalCall,
+            8..=15 => AnnouncementType::Reserved(id),
+            _ => panic!(
+                "Invalid announcement_type value {} (must be less than 16)",
+                id
+            ),
+        }
+    }
+
+    pub fn id(self) -> u8 {
+        match self {
+            AnnouncementType::EmergencyAlarm => 0,
+            AnnouncementType::RoadTrafficFlash => 1,
+            AnnouncementType::PublicTransportFlash => 2,
+            AnnouncementType::WarningMessage => 3,
+            AnnouncementType::NewsFlash => 4,
+            AnnouncementType::WeatherFlash => 5,
+            AnnouncementType::EventAnnouncement => 6,
+            AnnouncementType::PersonalCall => 7,
+            AnnouncementType::Reserved(id) => id,
+        }
+    }
+}
+
+/// Where the audio of an announcement is to be found
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReferenceType {
+    /// The announcement is broadcast in the usual audio stream of the service
+    UsualAudioStream,
+    /// The announcement is broadcast in a separate audio stream that is part of the service
+    SeparateAudioStream,
+    /// The announcement is broadcast by a different service within the same transport stream
+    DifferentServiceSameTs,
+    /// The announcement is broadcast by a different service within a different transport stream
+    DifferentServiceDifferentTs,
+    Reserved(u8),
+}
+impl ReferenceType {
+    pub fn from_id(id: u8) -> ReferenceType {
+        match id {
+            0 => ReferenceType::UsualAudioStream,
+            1 => ReferenceType::SeparateAudioStream,
+            2 => ReferenceType::DifferentServiceSameTs,
+            3 => ReferenceType::DifferentServiceDifferentTs,
+            4..=7 => ReferenceType::Reserved(id),
+            _ => panic!("Invalid reference_type value {} (must be less than 8)", id),
+        }
+    }
+
+    /// `true` if the announcement entry includes the service and component carrying the
+    /// announcement
+    fn has_reference(id: u8) -> bool {
+        matches!(id, 1..=3)
+    }
+}
+
+/// One entry from an [`AnnouncementSupportDescriptor`]
+pub struct Announcement<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> Announcement<'buf> {
+    pub fn announcement_type(&self) -> AnnouncementType {
+        AnnouncementType::from_id(self.data[0] >> 4)
+    }
+
+    pub fn reference_type(&self) -> ReferenceType {
+        ReferenceType::from_id(self.data[0] & 0b111)
+    }
+
+    /// The service carrying the announcement, for reference types other than
+    /// `UsualAudioStream`
+    pub fn service(&self) -> Option<ServiceTriplet> {
+        if self.data.len() < 8 {
+            return None;
+        }
+        Some(ServiceTriplet::from_bytes(&self.data[1..7]))
+    }
+
+    /// The `component_tag` of the audio stream carrying the announcement within
+    /// [`service()`](#method.service)
+    pub fn component_tag(&self) -> Option<u8> {
+        self.data.get(7).copied()
+    }
+}
+impl<'buf> fmt::Debug for Announcement<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Announcement")
+            .field("announcement_type", &self.announcement_type())
+            .field("reference_type", &self.reference_type())
+            .field("service", &self.service())
+            .field("component_tag", &self.component_tag())
+            .finish()
+    }
+}
+
+fn announcement_len(data: &[u8]) -> Option<usize> {
+    let len = if ReferenceType::has_reference(data[0] & 0b111) {
+        8
+    } else {
+        1
+    };
+    if len > data.len() {
+        None
+    } else {
+        Some(len)
+    }
+}
+
+struct AnnouncementIterator<'buf> {
+    remaining_data: &'buf [u8],
+}
+impl<'buf> Iterator for AnnouncementIterator<'buf> {
+    type Item = Announcement<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_data.is_empty() {
+            return None;
+        }
+        // lengths were checked by AnnouncementSupportDescriptor::new()
+        let len = announcement_len(self.remaining_data).unwrap();
+        let (head, tail) = self.remaining_data.split_at(len);
+        self.remaining_data = tail;
+        Some(Announcement { data: head })
+    }
+}
+
+/// Lists the kinds of announcement supported by a service, and where the audio of each is
+/// broadcast, as defined in _ETSI EN 300 468_ section 6.2.3.
+pub struct AnnouncementSupportDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> AnnouncementSupportDescriptor<'buf> {
+    pub const TAG: u8 = 0x6E;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<AnnouncementSupportDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 2 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 2,
+            });
+        }
+        let mut pos = 2;
+        while pos < data.len() {
+            match announcement_len(&data[pos..]) {
+                Some(len) => pos += len,
+                None => {
+                    return Err(descriptor::DescriptorError::NotEnoughData {
+                        tag,
+                        actual: data.len(),
+                        expected: pos + 8,
+                    })
+                }
+            }
+        }
+        Ok(AnnouncementSupportDescriptor { data })
+    }
+
+    /// Bit flags indicating the supported announcement types, with bit 0 (the least significant)
+    /// corresponding to `AnnouncementType::EmergencyAlarm`, and so on.
+    pub fn announcement_support_indicator(&self) -> u16 {
+        u16::from(self.data[0]) << 8 | u16::from(self.data[1])
+    }
+
+    /// `true` if the `announcement_support_indicator` flags the given announcement type
+    pub fn supports(&self, announcement_type: AnnouncementType) -> bool {
+        self.announcement_support_indicator() & (1 << announcement_type.id()) != 0
+    }
+
+    pub fn announcements(&self) -> impl Iterator<Item = Announcement<'buf>> {
+        AnnouncementIterator {
+            remaining_data: &self.data[2..],
+        }
+    }
+
+    /// The entry describing the given announcement type, if it is supported
+    pub fn announcement(&self, announcement_type: AnnouncementType) -> Option<Announcement<'buf>> {
+        if !self.supports(announcement_type) {
+            return None;
+        }
+        self.announcements()
+            .find(|a| a.announcement_type() == announcement_type)
+    }
+}
+impl<'buf> fmt::Debug for AnnouncementSupportDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("AnnouncementSupportDescriptor")
+            .field(
+                "announcement_support_indicator",
+                &format_args!("{:#06x}", self.announcement_support_indicator()),
+            )
+            .field("announcements", &self.announcements().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[rustfmt::skip]
+    const DATA: [u8; 19] = [
+        0b0000_0000, 0b0001_0011,
+        // emergency alarm, in a different service of another TS
+        0b0000_1011, 0x00, 0x01, 0x00, 0x02, 0x10, 0x01, 0x05,
+        // road traffic flash, in the usual audio stream
+        0b0001_1000,
+        // news flash, in a separate audio stream of the service
+        0b0100_1001, 0x00, 0x01, 0x00, 0x03, 0x10, 0x02, 0x06,
+    ];
+
+    #[test]
+    fn announcement_support_descriptor() {
+        let desc = AnnouncementSupportDescriptor::new(0x6E, &DATA).unwrap();
+        assert!(desc.supports(AnnouncementType::EmergencyAlarm));
+        assert!(desc.supports(AnnouncementType::RoadTrafficFlash));
+        assert!(!desc.supports(AnnouncementType::WeatherFlash));
+        assert_eq!(3, desc.announcements().count());
+
+        let emergency = desc.announcement(AnnouncementType::EmergencyAlarm).unwrap();
+        assert_eq!(
+            ReferenceType::DifferentServiceDifferentTs,
+            emergency.reference_type()
+        );
+        assert_eq!(
+            Some(ServiceTriplet {
+                original_network_id: 1,
+                transport_stream_id: 2,
+                service_id: 0x1001,
+            }),
+            emergency.service()
+        );
+        assert_eq!(Some(5), emergency.component_tag());
+
+        let traffic = desc
+            .announcement(AnnouncementType::RoadTrafficFlash)
+            .unwrap();
+        assert_eq!(ReferenceType::UsualAudioStream, traffic.reference_type());
+        assert_eq!(None, traffic.service());
+        assert_eq!(None, traffic.component_tag());
+
+        assert!(desc.announcement(AnnouncementType::WeatherFlash).is_none());
+        assert!(AnnouncementSupportDescriptor::new(0x6E, &DATA[..18]).is_err());
+        assert!(AnnouncementSupportDescriptor::new(0x6E, &DATA[..1]).is_err());
+    }
+}
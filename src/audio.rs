@@ -0,0 +1,1501 @@
+//! Descriptors identifying the audio coding used by an elementary stream (normally one with
+//! `stream_type` 0x06, _private PES data_, which otherwise gives no indication of its content).
+//!
+//! AC-3 and Enhanced AC-3 are described by _ETSI EN 300 468_ annex D, DTS and DTS-HD by annex G,
+//! AAC by annex H and AC-4 by annex D.
+//!
+//! Also provides the _ISO 639 Language Descriptor_ (tag 0x0A) and _Supplementary Audio
+//! Descriptor_ (extension tag 0x06), which describe the language and purpose of an audio stream.
+//! See [`StreamAccessibility`](../accessibility/struct.StreamAccessibility.html) for combining
+//! these with the other audio descriptors.
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Number of optional single-byte fields present, given the flags byte and a mask of the flags
+/// which signal such a field.
+fn flagged_field_count(flags: u8, mask: u8) -> usize {
+    (flags & mask).count_ones() as usize
+}
+
+/// The optional single-byte field signalled by bit `flag` of the flags byte (`data[0]`), if
+/// present.  Fields appear in the same order as their flags, most-significant bit first.
+fn flagged_field(data: &[u8], mask: u8, flag: u8) -> Option<u8> {
+    if data[0] & flag == 0 {
+        return None;
+    }
+    let preceding = flagged_field_count(data[0], mask & !(flag | (flag - 1)));
+    Some(data[1 + preceding])
+}
+
+fn check_flagged_fields(
+    tag: u8,
+    data: &[u8],
+    mask: u8,
+) -> Result<usize, descriptor::DescriptorError> {
+    if data.is_empty() {
+        return Err(descriptor::DescriptorError::NotEnoughData {
+            tag,
+            actual: 0,
+            expected: 1,
+        });
+    }
+    let expected = 1 + flagged_field_count(data[0], mask);
+    if data.len() < expected {
+        return Err(descriptor::DescriptorError::NotEnoughData {
+            tag,
+            actual: data.len(),
+            expected,
+        });
+    }
+    Ok(expected)
+}
+
+/// The kind of audio service, from an [`Ac3ComponentType`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Ac3ServiceType {
+    CompleteMain,
+    MusicAndEffects,
+    VisuallyImpaired,
+    HearingImpaired,
+    Dialogue,
+    Commentary,
+    Emergency,
+    Voiceover,
+    Karaoke,
+}
+
+/// The channel configuration, from an [`Ac3ComponentType`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Ac3Channels {
+    Mono,
+    /// Two independent mono channels
+    DualMono,
+    Stereo,
+    /// Two channel stereo, with matrix-encoded surround (e.g. _Dolby Surround_)
+    SurroundEncodedStereo,
+    /// More than two channels
+    Multichannel,
+    /// More than 5.1 channels (Enhanced AC-3 only)
+    MultichannelOver5_1,
+    /// The elementary stream contains multiple independent programmes (Enhanced AC-3 only)
+    MultiplePrograms,
+    Reserved,
+}
+
+/// The `component_type` value of the AC-3 and Enhanced AC-3 descriptors, as defined in
+/// _ETSI EN 300 468_ table D.1.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Ac3ComponentType(pub u8);
+impl Ac3ComponentType {
+    /// `true` if the stream is Enhanced AC-3, `false` if AC-3
+    pub fn enhanced_ac3(self) -> bool {
+        self.0 & 0b1000_0000 != 0
+    }
+
+    /// `true` if the stream can be presented on its own, without being mixed with another
+    pub fn full_service(self) -> bool {
+        self.0 & 0b0100_0000 != 0
+    }
+
+    pub fn service_type(self) -> Ac3ServiceType {
+        match (self.0 >> 3) & 0b111 {
+            0 => Ac3ServiceType::CompleteMain,
+            1 => Ac3ServiceType::MusicAndEffects,
+            2 => Ac3ServiceType::VisuallyImpaired,
+            3 => Ac3ServiceType::HearingImpaired,
+            4 => Ac3ServiceType::Dialogue,
+            5 => Ac3ServiceType::Commentary,
+            6 => Ac3ServiceType::Emergency,
+            7 => {
+                if self.0 & 0b111 == 0 {
+                    Ac3ServiceType::Voiceover
+                } else {
+                    Ac3ServiceType::Karaoke
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn channels(self) -> Ac3Channels {
+        match self.0 & 0b111 {
+            0 => Ac3Channels::Mono,
+            1 => Ac3Channels::DualMono,
+            2 => Ac3Channels::Stereo,
+            3 => Ac3Channels::SurroundEncodedStereo,
+            4 => Ac3Channels::Multichannel,
+            5 => Ac3Channels::MultichannelOver5_1,
+            6 => Ac3Channels::MultiplePrograms,
+            7 => Ac3Channels::Reserved,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Identifies an AC-3 elementary stream, as defined in _ETSI EN 300 468_ annex D.
+pub struct Ac3Descriptor<'buf> {
+    data: &'buf [u8],
+    additional_info_start: usize,
+}
+impl<'buf> Ac3Descriptor<'buf> {
+    pub const TAG: u8 = 0x6A;
+    const FIELDS: u8 = 0b1111_0000;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<Ac3Descriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        let additional_info_start = check_flagged_fields(tag, data, Self::FIELDS)?;
+        Ok(Ac3Descriptor {
+            data,
+            additional_info_start,
+        })
+    }
+
+    pub fn component_type(&self) -> Option<Ac3ComponentType> {
+        flagged_field(self.data, Self::FIELDS, 0b1000_0000).map(Ac3ComponentType)
+    }
+
+    /// The AC-3 coding version
+    pub fn bsid(&self) -> Option<u8> {
+        flagged_field(self.data, Self::FIELDS, 0b0100_0000)
+    }
+
+    /// Identifies the main audio service, allowing associated services to refer to it
+    pub fn mainid(&self) -> Option<u8> {
+        flagged_field(self.data, Self::FIELDS, 0b0010_0000)
+    }
+
+    /// For an associated service, a bitmask of the `mainid` values of the main services it may
+    /// be combined with
+    pub fn asvc(&self) -> Option<u8> {
+        flagged_field(self.data, Self::FIELDS, 0b0001_0000)
+    }
+
+    pub fn additional_info(&self) -> &'buf [u8] {
+        &self.data[self.additional_info_start..]
+    }
+}
+impl<'buf> fmt::Debug for Ac3Descriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Ac3Descriptor")
+            .field("component_type", &self.component_type())
+            .field("bsid", &self.bsid())
+            .field("mainid", &self.mainid())
+            .field("asvc", &self.asvc())
+            .finish()
+    }
+}
+
+/// Identifies an Enhanced AC-3 elementary stream, as defined in _ETSI EN 300 468_ annex D.
+pub struct EnhancedAc3Descriptor<'buf> {
+    data: &'buf [u8],
+    additional_info_start: usize,
+}
+impl<'buf> EnhancedAc3Descriptor<'buf> {
+    pub const TAG: u8 = 0x7A;
+    // mixinfoexists (0b0000_1000) is a flag with no associated field
+    const FIELDS: u8 = 0b1111_0111;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<EnhancedAc3Descriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        let additional_info_start = check_flagged_fields(tag, data, Self::FIELDS)?;
+        Ok(EnhancedAc3Descriptor {
+            data,
+            additional_info_start,
+        })
+    }
+
+    pub fn component_type(&self) -> Option<Ac3ComponentType> {
+        flagged_field(self.data, Self::FIELDS, 0b1000_0000).map(Ac3ComponentType)
+    }
+
+    /// The Enhanced AC-3 coding version
+    pub fn bsid(&self) -> Option<u8> {
+        flagged_field(self.data, Self::FIELDS, 0b0100_0000)
+    }
+
+    /// Identifies the main audio service, allowing associated services to refer to it
+    pub fn mainid(&self) -> Option<u8> {
+        flagged_field(self.data, Self::FIELDS, 0b0010_0000)
+    }
+
+    /// For an associated service, a bitmask of the `mainid` values of the main services it may
+    /// be combined with
+    pub fn asvc(&self) -> Option<u8> {
+        flagged_field(self.data, Self::FIELDS, 0b0001_0000)
+    }
+
+    /// `true` if the stream carries metadata for mixing with an associated audio service
+    pub fn mixinfoexists(&self) -> bool {
+        self.data[0] & 0b0000_1000 != 0
+    }
+
+    /// The types of the (up to three) additional independent substreams present in the
+    /// elementary stream
+    pub fn substreams(&self) -> [Option<Ac3ComponentType>; 3] {
+        [0b100, 0b010, 0b001]
+            .map(|flag| flagged_field(self.data, Self::FIELDS, flag).map(Ac3ComponentType))
+    }
+
+    pub fn additional_info(&self) -> &'buf [u8] {
+        &self.data[self.additional_info_start..]
+    }
+}
+impl<'buf> fmt::Debug for EnhancedAc3Descriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("EnhancedAc3Descriptor")
+            .field("component_type", &self.component_type())
+            .field("bsid", &self.bsid())
+            .field("mainid", &self.mainid())
+            .field("asvc", &self.asvc())
+            .field("mixinfoexists", &self.mixinfoexists())
+            .field("substreams", &self.substreams())
+            .finish()
+    }
+}
+
+/// Identifies a DTS coherent acoustics elementary stream, as defined in _ETSI EN 300 468_
+/// annex G.
+pub struct DtsDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> DtsDescriptor<'buf> {
+    pub const TAG: u8 = 0x7B;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<DtsDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 5 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 5,
+            });
+        }
+        Ok(DtsDescriptor { data })
+    }
+
+    fn bits(&self) -> u64 {
+        self.data[..5]
+            .iter()
+            .fold(0, |acc, b| acc << 8 | u64::from(*b))
+    }
+
+    pub fn sample_rate_code(&self) -> u8 {
+        (self.bits() >> 36) as u8 & 0b1111
+    }
+
+    /// The sampling rate in Hz indicated by `sample_rate_code`, or `None` for invalid codes
+    pub fn sample_rate(&self) -> Option<u32> {
+        match self.sample_rate_code() {
+            0b0001 => Some(8_000),
+            0b0010 => Some(16_000),
+            0b0011 => Some(32_000),
+            0b0110 => Some(11_025),
+            0b0111 => Some(22_050),
+            0b1000 => Some(44_100),
+            0b1011 => Some(12_000),
+            0b1100 => Some(24_000),
+            0b1101 => Some(48_000),
+            _ => None,
+        }
+    }
+
+    pub fn bit_rate_code(&self) -> u8 {
+        (self.bits() >> 30) as u8 & 0b11_1111
+    }
+
+    /// Number of PCM sample blocks per frame
+    pub fn nblks(&self) -> u8 {
+        (self.bits() >> 23) as u8 & 0b111_1111
+    }
+
+    /// Number of bytes per frame
+    pub fn fsize(&self) -> u16 {
+        (self.bits() >> 9) as u16 & 0b11_1111_1111_1111
+    }
+
+    /// Channel arrangement, as given by table G.4 of _ETSI EN 300 468_
+    pub fn surround_mode(&self) -> u8 {
+        (self.bits() >> 3) as u8 & 0b11_1111
+    }
+
+    /// Number of full-bandwidth channels implied by `surround_mode`, or `None` for user-defined
+    /// and reserved modes
+    pub fn channel_count(&self) -> Option<u8> {
+        match self.surround_mode() {
+            0b00_0000 => Some(1),
+            0b00_0001..=0b00_0100 => Some(2),
+            0b00_0101 | 0b00_0110 => Some(3),
+            0b00_0111 | 0b00_1000 => Some(4),
+            0b00_1001 => Some(5),
+            _ => None,
+        }
+    }
+
+    /// `true` if a low frequency effects channel is present
+    pub fn lfe_flag(&self) -> bool {
+        self.data[4] & 0b100 != 0
+    }
+
+    pub fn extended_surround_flag(&self) -> u8 {
+        self.data[4] & 0b11
+    }
+
+    pub fn additional_info(&self) -> &'buf [u8] {
+        &self.data[5..]
+    }
+}
+impl<'buf> fmt::Debug for DtsDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DtsDescriptor")
+            .field("sample_rate", &self.sample_rate())
+            .field("bit_rate_code", &self.bit_rate_code())
+            .field("nblks", &self.nblks())
+            .field("fsize", &self.fsize())
+            .field("surround_mode", &self.surround_mode())
+            .field("lfe_flag", &self.lfe_flag())
+            .field("extended_surround_flag", &self.extended_surround_flag())
+            .finish()
+    }
+}
+
+/// The type of an AAC stream, using the same values as the `component_type` of a
+/// [`ComponentDescriptor`](../component/struct.ComponentDescriptor.html) with `stream_content`
+/// 0x6, given in table 26 of _ETSI EN 300 468_.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AacType {
+    HeAacMono,
+    HeAacStereo,
+    HeAacSurround,
+    HeAacAudioDescription,
+    HeAacHardOfHearing,
+    HeAacReceiverMixSupplementary,
+    HeAacV2Stereo,
+    HeAacV2AudioDescription,
+    HeAacV2HardOfHearing,
+    HeAacV2ReceiverMixSupplementary,
+    HeAacReceiverMixAudioDescription,
+    HeAacBroadcastMixAudioDescription,
+    HeAacV2ReceiverMixAudioDescription,
+    HeAacV2BroadcastMixAudioDescription,
+    /// HE-AAC or HE-AAC v2 with SAOC-DE ancillary data
+    HeAacSaocDe,
+    Reserved(u8),
+}
+impl AacType {
+    pub fn from_id(id: u8) -> AacType {
+        match id {
+            0x01 => AacType::HeAacMono,
+            0x03 => AacType::HeAacStereo,
+            0x05 => AacType::HeAacSurround,
+            0x40 => AacType::HeAacAudioDescription,
+            0x41 => AacType::HeAacHardOfHearing,
+            0x42 => AacType::HeAacReceiverMixSupplementary,
+            0x43 => AacType::HeAacV2Stereo,
+            0x44 => AacType::HeAacV2AudioDescription,
+            0x45 => AacType::HeAacV2HardOfHearing,
+            0x46 => AacType::HeAacV2ReceiverMixSupplementary,
+            0x47 => AacType::HeAacReceiverMixAudioDescription,
+            0x48 => AacType::HeAacBroadcastMixAudioDescription,
+            0x49 => AacType::HeAacV2ReceiverMixAudioDescription,
+            0x4A => AacType::HeAacV2BroadcastMixAudioDescription,
+            0xA0 => AacType::HeAacSaocDe,
+            _ => AacType::Reserved(id),
+        }
+    }
+
+    /// `true` for audio description for the visually impaired, whether ready-mixed or to be
+    /// mixed by the receiver
+    pub fn is_audio_description(self) -> bool {
+        matches!(
+            self,
+            AacType::HeAacAudioDescription
+                | AacType::HeAacV2AudioDescription
+                | AacType::HeAacReceiverMixAudioDescription
+                | AacType::HeAacBroadcastMixAudioDescription
+                | AacType::HeAacV2ReceiverMixAudioDescription
+                | AacType::HeAacV2BroadcastMixAudioDescription
+        )
+    }
+}
+
+/// Identifies an MPEG-4 AAC, HE-AAC or HE-AAC v2 elementary stream, as defined in
+/// _ETSI EN 300 468_ annex H.
+pub struct AacDescriptor<'buf> {
+    data: &'buf [u8],
+    additional_info_start: usize,
+}
+impl<'buf> AacDescriptor<'buf> {
+    pub const TAG: u8 = 0x7C;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<AacDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        // earlier revisions of the spec had only the profile_and_level field
+        let additional_info_start = match data.get(1) {
+            None => 1,
+            Some(flags) if flags & 0b1000_0000 != 0 => 3,
+            Some(_) => 2,
+        };
+        if data.len() < additional_info_start {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: additional_info_start,
+            });
+        }
+        Ok(AacDescriptor {
+            data,
+            additional_info_start,
+        })
+    }
+
+    /// The MPEG-4 audio profile and level, as defined in ISO/IEC 14496-3
+    pub fn profile_and_level(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// `true` if the stream carries SAOC-DE (dialogue enhancement) parametric data
+    pub fn saoc_de_flag(&self) -> bool {
+        self.data
+            .get(1)
+            .map(|f| f & 0b0100_0000 != 0)
+            .unwrap_or(false)
+    }
+
+    pub fn aac_type(&self) -> Option<AacType> {
+        if self.additional_info_start == 3 {
+            Some(AacType::from_id(self.data[2]))
+        } else {
+            None
+        }
+    }
+
+    pub fn additional_info(&self) -> &'buf [u8] {
+        &self.data[self.additional_info_start..]
+    }
+}
+impl<'buf> fmt::Debug for AacDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("AacDescriptor")
+            .field("profile_and_level", &self.profile_and_level())
+            .field("saoc_de_flag", &self.saoc_de_flag())
+            .field("aac_type", &self.aac_type())
+            .finish()
+    }
+}
+
+/// The channel mode given by an [`Ac4Descriptor`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Ac4ChannelMode {
+    Mono,
+    Stereo,
+    Multichannel,
+    Reserved,
+}
+
+/// Identifies an AC-4 elementary stream, as defined in _ETSI EN 300 468_ annex D.
+///
+/// Carried within an [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html);
+/// construct using its `selector_bytes()`.  Whether a stream carries immersive (e.g. object-based)
+/// audio is only indicated within the `ac4_dsi` decoder specific information.
+pub struct Ac4Descriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> Ac4Descriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x15;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<Ac4Descriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        let not_enough = |expected| descriptor::DescriptorError::NotEnoughData {
+            tag: crate::extension::ExtensionDescriptor::TAG,
+            actual: data.len(),
+            expected,
+        };
+        let flags = *data.first().ok_or_else(|| not_enough(1))?;
+        let mut expected = 1;
+        if flags & 0b1000_0000 != 0 {
+            expected += 1;
+        }
+        if flags & 0b0100_0000 != 0 {
+            let toc_len = data.get(expected).ok_or_else(|| not_enough(expected + 1))?;
+            expected += 1 + usize::from(*toc_len);
+        }
+        if data.len() < expected {
+            return Err(not_enough(expected));
+        }
+        Ok(Ac4Descriptor { data })
+    }
+
+    fn ac4_config_flag(&self) -> bool {
+        self.data[0] & 0b1000_0000 != 0
+    }
+
+    fn ac4_toc_flag(&self) -> bool {
+        self.data[0] & 0b0100_0000 != 0
+    }
+
+    /// `true` if dialogue enhancement is enabled, or `None` if not signalled
+    pub fn ac4_dialog_enhancement_enabled(&self) -> Option<bool> {
+        if self.ac4_config_flag() {
+            Some(self.data[1] & 0b1000_0000 != 0)
+        } else {
+            None
+        }
+    }
+
+    pub fn ac4_channel_mode(&self) -> Option<Ac4ChannelMode> {
+        if self.ac4_config_flag() {
+            Some(match (self.data[1] >> 5) & 0b11 {
+                0 => Ac4ChannelMode::Mono,
+                1 => Ac4ChannelMode::Stereo,
+                2 => Ac4ChannelMode::Multichannel,
+                3 => Ac4ChannelMode::Reserved,
+                _ => unreachable!(),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn toc_start(&self) -> usize {
+        if self.ac4_config_flag() {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// The AC-4 decoder specific information (the `ac4_dsi()` structure of _ETSI TS 103 190_), if
+    /// present
+    pub fn ac4_dsi(&self) -> Option<&'buf [u8]> {
+        if self.ac4_toc_flag() {
+            let start = self.toc_start();
+            let len = usize::from(self.data[start]);
+            Some(&self.data[start + 1..start + 1 + len])
+        } else {
+            None
+        }
+    }
+
+    pub fn additional_info(&self) -> &'buf [u8] {
+        let start = self.toc_start();
+        match self.ac4_dsi() {
+            Some(dsi) => &self.data[start + 1 + dsi.len()..],
+            None => &self.data[start..],
+        }
+    }
+}
+impl<'buf> fmt::Debug for Ac4Descriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Ac4Descriptor")
+            .field(
+                "ac4_dialog_enhancement_enabled",
+                &self.ac4_dialog_enhancement_enabled(),
+            )
+            .field("ac4_channel_mode", &self.ac4_channel_mode())
+            .field("ac4_dsi", &self.ac4_dsi())
+            .finish()
+    }
+}
+
+/// A substream of a DTS-HD elementary stream, from a [`DtsHdDescriptor`]
+pub struct DtsHdSubstream<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> DtsHdSubstream<'buf> {
+    /// Number of audio assets in the substream
+    pub fn num_assets(&self) -> u8 {
+        (self.data[0] >> 5) + 1
+    }
+
+    /// Number of channels, including any LFE channel
+    pub fn channel_count(&self) -> u8 {
+        self.data[0] & 0b1_1111
+    }
+
+    /// `true` if a low frequency effects channel is present
+    pub fn lfe_flag(&self) -> bool {
+        self.data[1] & 0b1000_0000 != 0
+    }
+
+    /// The sampling frequency code, as given by table G.7 of _ETSI EN 300 468_
+    pub fn sampling_frequency(&self) -> u8 {
+        (self.data[1] >> 3) & 0b1111
+    }
+
+    /// `true` if the sample resolution exceeds 16 bits
+    pub fn sample_resolution(&self) -> bool {
+        self.data[1] & 0b100 != 0
+    }
+
+    /// The undecoded `asset_info()` structures of this substream
+    pub fn asset_info(&self) -> &'buf [u8] {
+        &self.data[2..]
+    }
+}
+impl<'buf> fmt::Debug for DtsHdSubstream<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DtsHdSubstream")
+            .field("num_assets", &self.num_assets())
+            .field("channel_count", &self.channel_count())
+            .field("lfe_flag", &self.lfe_flag())
+            .field("sampling_frequency", &self.sampling_frequency())
+            .field("sample_resolution", &self.sample_resolution())
+            .finish()
+    }
+}
+
+/// Identifies a DTS-HD elementary stream, as defined in _ETSI EN 300 468_ annex G.
+///
+/// Carried within an [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html);
+/// construct using its `selector_bytes()`.
+pub struct DtsHdDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> DtsHdDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x0E;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<DtsHdDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        let not_enough = |expected| descriptor::DescriptorError::NotEnoughData {
+            tag: crate::extension::ExtensionDescriptor::TAG,
+            actual: data.len(),
+            expected,
+        };
+        let flags = *data.first().ok_or_else(|| not_enough(1))?;
+        let mut pos = 1;
+        for _ in 0..(flags & 0b1111_1000).count_ones() {
+            let substream_length = data.get(pos).ok_or_else(|| not_enough(pos + 1))?;
+            let substream_length = usize::from(*substream_length);
+            if substream_length < 2 {
+                return Err(not_enough(pos + 3));
+            }
+            pos += 1 + substream_length;
+            if data.len() < pos {
+                return Err(not_enough(pos));
+            }
+        }
+        Ok(DtsHdDescriptor { data })
+    }
+
+    /// Iterates over the present substreams, in the order core, substream 0, 1, 2, 3, yielding
+    /// the flag bit mask identifying each along with its information
+    fn substreams(&self) -> impl Iterator<Item = (u8, DtsHdSubstream<'buf>)> + 'buf {
+        let data = self.data;
+        let flags = data[0];
+        let mut pos = 1;
+        [
+            0b1000_0000,
+            0b0100_0000,
+            0b0010_0000,
+            0b0001_0000,
+            0b0000_1000,
+        ]
+        .into_iter()
+        .filter(move |flag| flags & flag != 0)
+        .map(move |flag| {
+            let len = usize::from(data[pos]);
+            let substream = DtsHdSubstream {
+                data: &data[pos + 1..pos + 1 + len],
+            };
+            pos += 1 + len;
+            (flag, substream)
+        })
+    }
+
+    /// The core substream (compatible with decoders supporting only [`DtsDescriptor`] streams)
+    pub fn substream_core(&self) -> Option<DtsHdSubstream<'buf>> {
+        self.substreams()
+            .find(|(flag, _)| *flag == 0b1000_0000)
+            .map(|(_, s)| s)
+    }
+
+    /// The extension substreams 0 to 3
+    pub fn extension_substreams(&self) -> [Option<DtsHdSubstream<'buf>>; 4] {
+        let mut result = [None, None, None, None];
+        for (flag, substream) in self.substreams() {
+            match flag {
+                0b0100_0000 => result[0] = Some(substream),
+                0b0010_0000 => result[1] = Some(substream),
+                0b0001_0000 => result[2] = Some(substream),
+                0b0000_1000 => result[3] = Some(substream),
+                _ => (),
+            }
+        }
+        result
+    }
+
+    pub fn additional_info(&self) -> &'buf [u8] {
+        let end = self
+            .substreams()
+            .fold(1, |pos, (_, s)| pos + 1 + s.data.len());
+        &self.data[end..]
+    }
+}
+impl<'buf> fmt::Debug for DtsHdDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DtsHdDescriptor")
+            .field("substream_core", &self.substream_core())
+            .field("extension_substreams", &self.extension_substreams())
+            .finish()
+    }
+}
+
+/// Identifies a DTS-UHD elementary stream, as defined in _ETSI EN 300 468_ annex G.
+///
+/// Carried within an [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html);
+/// construct using its `selector_bytes()`.
+pub struct DtsUhdDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> DtsUhdDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x21;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<DtsUhdDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        if data.len() < 2 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected: 2,
+            });
+        }
+        Ok(DtsUhdDescriptor { data })
+    }
+
+    /// The decoder profile required to decode the stream
+    pub fn decoder_profile(&self) -> u8 {
+        (self.data[0] >> 2) + 2
+    }
+
+    /// The number of audio samples per frame at a 48 kHz base rate
+    pub fn frame_duration(&self) -> u32 {
+        512 << (self.data[0] & 0b11)
+    }
+
+    /// The maximum size of a frame payload, in bytes
+    pub fn max_payload(&self) -> u32 {
+        2048 << (self.data[1] >> 5)
+    }
+
+    /// Distinguishes multiple DTS-UHD streams carried with the same `stream_type`
+    pub fn stream_index(&self) -> u8 {
+        self.data[1] & 0b111
+    }
+
+    pub fn codec_selector(&self) -> &'buf [u8] {
+        &self.data[2..]
+    }
+}
+impl<'buf> fmt::Debug for DtsUhdDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DtsUhdDescriptor")
+            .field("decoder_profile", &self.decoder_profile())
+            .field("frame_duration", &self.frame_duration())
+            .field("max_payload", &self.max_payload())
+            .field("stream_index", &self.stream_index())
+            .finish()
+    }
+}
+
+/// The `audio_type` of an [`Iso639Language`] entry, as defined in _ISO/IEC 13818-1_ table 2-60.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AudioType {
+    Undefined,
+    CleanEffects,
+    HearingImpaired,
+    VisualImpairedCommentary,
+    UserPrivate(u8),
+    Reserved(u8),
+}
+impl AudioType {
+    pub fn from_id(id: u8) -> AudioType {
+        match id {
+            0x00 => AudioType::Undefined,
+            0x01 => AudioType::CleanEffects,
+            0x02 => AudioType::HearingImpaired,
+            0x03 => AudioType::VisualImpairedCommentary,
+            0x04..=0x7F => AudioType::UserPrivate(id),
+            _ => AudioType::Reserved(id),
+        }
+    }
+}
+
+/// A single 4-byte entry from an [`Iso639LanguageDescriptor`].
+pub struct Iso639Language<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> Iso639Language<'buf> {
+    const SIZE: usize = 4;
+
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn audio_type(&self) -> AudioType {
+        AudioType::from_id(self.data[3])
+    }
+}
+impl<'buf> fmt::Debug for Iso639Language<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Iso639Language")
+            .field("language_code", &self.language_code_str())
+            .field("audio_type", &self.audio_type())
+            .finish()
+    }
+}
+
+/// Gives the language(s) of an elementary stream, as defined in _ISO/IEC 13818-1_ section
+/// 2.6.18.
+pub struct Iso639LanguageDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> Iso639LanguageDescriptor<'buf> {
+    pub const TAG: u8 = 0x0A;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<Iso639LanguageDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(Iso639LanguageDescriptor { data })
+    }
+
+    pub fn languages(&self) -> impl Iterator<Item = Iso639Language<'buf>> {
+        self.data
+            .chunks_exact(Iso639Language::SIZE)
+            .map(|data| Iso639Language { data })
+    }
+}
+impl<'buf> fmt::Debug for Iso639LanguageDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Iso639LanguageDescriptor")
+            .field("languages", &self.languages().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// How a [`SupplementaryAudioDescriptor`] stream is to be presented
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MixType {
+    /// The stream must be mixed by the receiver with a separate main audio stream
+    Supplementary,
+    /// The stream is complete, and can be presented on its own (i.e. it is _broadcast mixed_)
+    Independent,
+}
+
+/// The purpose of a [`SupplementaryAudioDescriptor`] stream, as given in
+/// _ETSI EN 300 468_ table J.3.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EditorialClassification {
+    MainAudio,
+    /// Audio description for the visually impaired
+    AudioDescription,
+    /// Clean audio for the hearing impaired
+    CleanAudio,
+    /// Spoken subtitles for the visually impaired
+    SpokenSubtitles,
+    Reserved(u8),
+    /// Supplementary audio whose purpose is not specified
+    UnspecifiedSupplementary,
+    UserDefined(u8),
+}
+impl EditorialClassification {
+    pub fn from_id(id: u8) -> EditorialClassification {
+        match id {
+            0x00 => EditorialClassification::MainAudio,
+            0x01 => EditorialClassification::AudioDescription,
+            0x02 => EditorialClassification::CleanAudio,
+            0x03 => EditorialClassification::SpokenSubtitles,
+            0x04..=0x16 => EditorialClassification::Reserved(id),
+            0x17 => EditorialClassification::UnspecifiedSupplementary,
+            0x18..=0x1F => EditorialClassification::UserDefined(id),
+            _ => panic!(
+                "Invalid editorial_classification value {} (must be less than 0x20)",
+                id
+            ),
+        }
+    }
+}
+
+/// Describes the purpose of an audio stream supplementing (or replacing) the main audio of a
+/// service, as defined in _ETSI EN 300 468_ section 6.4.11 and annex J.
+///
+/// This is carried within an [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct SupplementaryAudioDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> SupplementaryAudioDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x06;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<SupplementaryAudioDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        let expected = if data.first().map_or(false, |b| b & 0b0000_0001 != 0) {
+            4
+        } else {
+            1
+        };
+        if data.len() < expected {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected,
+            });
+        }
+        Ok(SupplementaryAudioDescriptor { data })
+    }
+
+    pub fn mix_type(&self) -> MixType {
+        if self.data[0] & 0b1000_0000 != 0 {
+            MixType::Independent
+        } else {
+            MixType::Supplementary
+        }
+    }
+
+    pub fn editorial_classification(&self) -> EditorialClassification {
+        EditorialClassification::from_id((self.data[0] >> 2) & 0b1_1111)
+    }
+
+    fn language_code_present(&self) -> bool {
+        self.data[0] & 0b0000_0001 != 0
+    }
+
+    /// Three-character ISO 639-2 language code, if the stream's language differs from that
+    /// given by an [`Iso639LanguageDescriptor`]
+    pub fn language_code(&self) -> Option<&'buf [u8]> {
+        if self.language_code_present() {
+            Some(&self.data[1..4])
+        } else {
+            None
+        }
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if absent or if the bytes
+    /// are not valid UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        self.language_code()
+            .and_then(|code| std::str::from_utf8(code).ok())
+    }
+
+    pub fn private_data(&self) -> &'buf [u8] {
+        if self.language_code_present() {
+            &self.data[4..]
+        } else {
+            &self.data[1..]
+        }
+    }
+}
+impl<'buf> fmt::Debug for SupplementaryAudioDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("SupplementaryAudioDescriptor")
+            .field("mix_type", &self.mix_type())
+            .field("editorial_classification", &self.editorial_classification())
+            .field("language_code", &self.language_code_str())
+            .finish()
+    }
+}
+
+/// The loudspeaker configuration a [`Preselection`] is intended for
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AudioRenderingIndication {
+    NoPreference,
+    Stereo,
+    /// Two-dimensional multichannel, e.g. 5.1
+    TwoDimensional,
+    /// Three-dimensional multichannel, including height channels
+    ThreeDimensional,
+    Headphones,
+    Reserved(u8),
+}
+impl AudioRenderingIndication {
+    pub fn from_id(id: u8) -> AudioRenderingIndication {
+        match id {
+            0 => AudioRenderingIndication::NoPreference,
+            1 => AudioRenderingIndication::Stereo,
+            2 => AudioRenderingIndication::TwoDimensional,
+            3 => AudioRenderingIndication::ThreeDimensional,
+            4 => AudioRenderingIndication::Headphones,
+            5..=7 => AudioRenderingIndication::Reserved(id),
+            _ => panic!(
+                "Invalid audio_rendering_indication value {} (must be less than 8)",
+                id
+            ),
+        }
+    }
+}
+
+/// A single audio experience offered by a next generation audio (e.g. AC-4 or MPEG-H) stream,
+/// from an [`AudioPreselectionDescriptor`]
+pub struct Preselection<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> Preselection<'buf> {
+    /// The size of the preselection at the start of `data`, or `Err` giving the number of bytes
+    /// required if `data` is too short
+    fn size(data: &[u8]) -> Result<usize, usize> {
+        if data.len() < 2 {
+            return Err(2);
+        }
+        let flags = data[1];
+        let mut size = 2;
+        if flags & 0b0000_1000 != 0 {
+            size += 3;
+        }
+        if flags & 0b0000_0100 != 0 {
+            size += 1;
+        }
+        if flags & 0b0000_0010 != 0 {
+            let num_aux_components = data.get(size).ok_or(size + 1)? >> 5;
+            size += 1 + usize::from(num_aux_components);
+        }
+        if flags & 0b0000_0001 != 0 {
+            let future_extension_length = data.get(size).ok_or(size + 1)? & 0b1_1111;
+            size += 1 + usize::from(future_extension_length);
+        }
+        if data.len() < size {
+            Err(size)
+        } else {
+            Ok(size)
+        }
+    }
+
+    /// Identifies the preselection within the audio stream, as given by the stream's own
+    /// metadata
+    pub fn preselection_id(&self) -> u8 {
+        self.data[0] >> 3
+    }
+
+    pub fn audio_rendering_indication(&self) -> AudioRenderingIndication {
+        AudioRenderingIndication::from_id(self.data[0] & 0b111)
+    }
+
+    /// `true` if the preselection includes audio description for the visually impaired
+    pub fn audio_description(&self) -> bool {
+        self.data[1] & 0b1000_0000 != 0
+    }
+
+    /// `true` if the preselection includes spoken subtitles for the visually impaired
+    pub fn spoken_subtitles(&self) -> bool {
+        self.data[1] & 0b0100_0000 != 0
+    }
+
+    /// `true` if the preselection has enhanced dialogue intelligibility
+    pub fn dialogue_enhancement(&self) -> bool {
+        self.data[1] & 0b0010_0000 != 0
+    }
+
+    /// `true` if the user may adjust the preselection's audio, for example the level of
+    /// individual components
+    pub fn interactivity_enabled(&self) -> bool {
+        self.data[1] & 0b0001_0000 != 0
+    }
+
+    fn language_code_present(&self) -> bool {
+        self.data[1] & 0b0000_1000 != 0
+    }
+
+    fn text_label_present(&self) -> bool {
+        self.data[1] & 0b0000_0100 != 0
+    }
+
+    fn multi_stream_info_present(&self) -> bool {
+        self.data[1] & 0b0000_0010 != 0
+    }
+
+    fn future_extension_present(&self) -> bool {
+        self.data[1] & 0b0000_0001 != 0
+    }
+
+    fn text_label_pos(&self) -> usize {
+        if self.language_code_present() {
+            5
+        } else {
+            2
+        }
+    }
+
+    fn multi_stream_info_pos(&self) -> usize {
+        self.text_label_pos() + usize::from(self.text_label_present())
+    }
+
+    fn future_extension_pos(&self) -> usize {
+        if self.multi_stream_info_present() {
+            self.multi_stream_info_pos() + 1 + self.aux_component_tags().len()
+        } else {
+            self.multi_stream_info_pos()
+        }
+    }
+
+    /// Three-character ISO 639-2 language code of the preselection's dialogue, if signalled
+    pub fn language_code(&self) -> Option<&'buf [u8]> {
+        if self.language_code_present() {
+            Some(&self.data[2..5])
+        } else {
+            None
+        }
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if absent or if the bytes
+    /// are not valid UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        self.language_code()
+            .and_then(|code| std::str::from_utf8(code).ok())
+    }
+
+    /// Identifies the [`MessageDescriptor`](../network_change/struct.MessageDescriptor.html)
+    /// (in the same descriptor loop) giving a textual label for the preselection, if any
+    pub fn message_id(&self) -> Option<u8> {
+        if self.text_label_present() {
+            Some(self.data[self.text_label_pos()])
+        } else {
+            None
+        }
+    }
+
+    /// The `component_tag` values of the streams, in addition to the one carrying this
+    /// descriptor, whose audio components are needed to decode the preselection
+    pub fn aux_component_tags(&self) -> &'buf [u8] {
+        if self.multi_stream_info_present() {
+            let pos = self.multi_stream_info_pos();
+            let num_aux_components = usize::from(self.data[pos] >> 5);
+            &self.data[pos + 1..pos + 1 + num_aux_components]
+        } else {
+            &[]
+        }
+    }
+
+    pub fn future_extension(&self) -> Option<&'buf [u8]> {
+        if self.future_extension_present() {
+            let pos = self.future_extension_pos();
+            Some(&self.data[pos + 1..])
+        } else {
+            None
+        }
+    }
+}
+impl<'buf> fmt::Debug for Preselection<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Preselection")
+            .field("preselection_id", &self.preselection_id())
+            .field(
+                "audio_rendering_indication",
+                &self.audio_rendering_indication(),
+            )
+            .field("audio_description", &self.audio_description())
+            .field("spoken_subtitles", &self.spoken_subtitles())
+            .field("dialogue_enhancement", &self.dialogue_enhancement())
+            .field("interactivity_enabled", &self.interactivity_enabled())
+            .field("language_code", &self.language_code_str())
+            .field("message_id", &self.message_id())
+            .field("aux_component_tags", &self.aux_component_tags())
+            .finish()
+    }
+}
+
+/// Lists the preselections (selectable audio experiences) offered by a next generation audio
+/// stream, as defined in _ETSI EN 300 468_ section 6.4.1.
+///
+/// This is carried within an [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct AudioPreselectionDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> AudioPreselectionDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x19;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<AudioPreselectionDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        let not_enough = |expected| descriptor::DescriptorError::NotEnoughData {
+            tag: crate::extension::ExtensionDescriptor::TAG,
+            actual: data.len(),
+            expected,
+        };
+        let num_preselections = data.first().ok_or_else(|| not_enough(1))? >> 3;
+        let mut pos = 1;
+        for _ in 0..num_preselections {
+            pos +=
+                Preselection::size(&data[pos..]).map_err(|expected| not_enough(pos + expected))?;
+        }
+        Ok(AudioPreselectionDescriptor { data })
+    }
+
+    pub fn num_preselections(&self) -> u8 {
+        self.data[0] >> 3
+    }
+
+    pub fn preselections(&self) -> impl Iterator<Item = Preselection<'buf>> + 'buf {
+        let data = self.data;
+        let mut pos = 1;
+        (0..self.num_preselections()).map(move |_| {
+            // lengths were checked by AudioPreselectionDescriptor::new()
+            let size = Preselection::size(&data[pos..]).unwrap();
+            let preselection = Preselection {
+                data: &data[pos..pos + size],
+            };
+            pos += size;
+            preselection
+        })
+    }
+}
+impl<'buf> fmt::Debug for AudioPreselectionDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("AudioPreselectionDescriptor")
+            .field("preselections", &self.preselections().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ac3_descriptor() {
+        // component_type and asvc present
+        let data = [0b1001_0000, 0b0100_0100, 0x03, 0xAA];
+        let desc = Ac3Descriptor::new(0x6A, &data).unwrap();
+        let component_type = desc.component_type().unwrap();
+        assert!(!component_type.enhanced_ac3());
+        assert!(component_type.full_service());
+        assert_eq!(Ac3ServiceType::CompleteMain, component_type.service_type());
+        assert_eq!(Ac3Channels::Multichannel, component_type.channels());
+        assert_eq!(None, desc.bsid());
+        assert_eq!(None, desc.mainid());
+        assert_eq!(Some(0x03), desc.asvc());
+        assert_eq!(&[0xAA], desc.additional_info());
+        assert!(Ac3Descriptor::new(0x6A, &data[..2]).is_err());
+    }
+
+    #[test]
+    fn enhanced_ac3_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            0b0110_1010, // bsid, mainid, mixinfoexists, substream2
+            0x10, // bsid
+            0x01, // mainid
+            0b1101_0010, // substream2: E-AC-3, full service, visually impaired, stereo
+        ];
+        let desc = EnhancedAc3Descriptor::new(0x7A, &data).unwrap();
+        assert_eq!(None, desc.component_type());
+        assert_eq!(Some(0x10), desc.bsid());
+        assert_eq!(Some(0x01), desc.mainid());
+        assert!(desc.mixinfoexists());
+        let substreams = desc.substreams();
+        assert_eq!(None, substreams[0]);
+        let ss2 = substreams[1].unwrap();
+        assert!(ss2.enhanced_ac3());
+        assert_eq!(Ac3ServiceType::VisuallyImpaired, ss2.service_type());
+        assert_eq!(Ac3Channels::Stereo, ss2.channels());
+        assert_eq!(None, substreams[2]);
+        assert!(desc.additional_info().is_empty());
+    }
+
+    #[test]
+    fn dts_descriptor() {
+        // sample_rate_code=0b1101, bit_rate_code=0b001111, nblks=15, fsize=1006,
+        // surround_mode=9, lfe_flag=1, extended_surround_flag=0
+        let bits: u64 = 0b1101 << 36 | 0b00_1111 << 30 | 15 << 23 | 1006 << 9 | 9 << 3 | 1 << 2;
+        let data = &bits.to_be_bytes()[3..];
+        let desc = DtsDescriptor::new(0x7B, data).unwrap();
+        assert_eq!(Some(48_000), desc.sample_rate());
+        assert_eq!(0b00_1111, desc.bit_rate_code());
+        assert_eq!(15, desc.nblks());
+        assert_eq!(1006, desc.fsize());
+        assert_eq!(Some(5), desc.channel_count());
+        assert!(desc.lfe_flag());
+        assert_eq!(0, desc.extended_surround_flag());
+    }
+
+    #[test]
+    fn aac_descriptor() {
+        let desc = AacDescriptor::new(0x7C, &[0x58, 0b1000_0000, 0x47]).unwrap();
+        assert_eq!(0x58, desc.profile_and_level());
+        assert!(!desc.saoc_de_flag());
+        assert_eq!(
+            Some(AacType::HeAacReceiverMixAudioDescription),
+            desc.aac_type()
+        );
+        assert!(desc.aac_type().unwrap().is_audio_description());
+        let desc = AacDescriptor::new(0x7C, &[0x58]).unwrap();
+        assert_eq!(None, desc.aac_type());
+        assert!(AacDescriptor::new(0x7C, &[0x58, 0b1000_0000]).is_err());
+    }
+
+    #[test]
+    fn ac4_descriptor() {
+        let data = [0b1100_0000, 0b1100_0000, 0x02, 0xAB, 0xCD, 0xEE];
+        let desc = Ac4Descriptor::new(0x15, &data).unwrap();
+        assert_eq!(Some(true), desc.ac4_dialog_enhancement_enabled());
+        assert_eq!(Some(Ac4ChannelMode::Multichannel), desc.ac4_channel_mode());
+        assert_eq!(Some(&[0xAB, 0xCD][..]), desc.ac4_dsi());
+        assert_eq!(&[0xEE], desc.additional_info());
+        assert!(Ac4Descriptor::new(0x15, &data[..4]).is_err());
+    }
+
+    #[test]
+    fn dts_hd_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            0b1010_0000, // core and substream 1
+            0x02, 0b0000_0110, 0b1110_1100, // core: 1 asset, 6 channels, LFE, 48kHz, >16 bits
+            0x04, 0b0010_1000, 0b0110_1000, 0x11, 0x22, // substream 1: 2 assets, 8 channels
+            0xFF,
+        ];
+        let desc = DtsHdDescriptor::new(0x0E, &data).unwrap();
+        let core = desc.substream_core().unwrap();
+        assert_eq!(1, core.num_assets());
+        assert_eq!(6, core.channel_count());
+        assert!(core.lfe_flag());
+        assert_eq!(0b1101, core.sampling_frequency());
+        assert!(core.sample_resolution());
+        let ext = desc.extension_substreams();
+        assert!(ext[0].is_none());
+        let ss1 = ext[1].as_ref().unwrap();
+        assert_eq!(2, ss1.num_assets());
+        assert_eq!(8, ss1.channel_count());
+        assert!(!ss1.lfe_flag());
+        assert_eq!(&[0x11, 0x22], ss1.asset_info());
+        assert_eq!(&[0xFF], desc.additional_info());
+        assert!(DtsHdDescriptor::new(0x0E, &data[..6]).is_err());
+    }
+
+    #[test]
+    fn dts_uhd_descriptor() {
+        let desc = DtsUhdDescriptor::new(0x21, &[0b0000_0101, 0b0100_0001]).unwrap();
+        assert_eq!(3, desc.decoder_profile());
+        assert_eq!(1024, desc.frame_duration());
+        assert_eq!(8192, desc.max_payload());
+        assert_eq!(1, desc.stream_index());
+    }
+
+    #[test]
+    fn iso_639_language_descriptor() {
+        let data = [b'e', b'n', b'g', 0x00, b'e', b'n', b'g', 0x03];
+        let desc = Iso639LanguageDescriptor::new(0x0A, &data).unwrap();
+        let langs: Vec<_> = desc.languages().collect();
+        assert_eq!(2, langs.len());
+        assert_eq!(Some("eng"), langs[0].language_code_str());
+        assert_eq!(AudioType::Undefined, langs[0].audio_type());
+        assert_eq!(AudioType::VisualImpairedCommentary, langs[1].audio_type());
+    }
+
+    #[test]
+    fn supplementary_audio_descriptor() {
+        // receiver-mix audio description, with language code and private data
+        let data = [0b0000_0101, b'c', b'y', b'm', 0xAA];
+        let desc = SupplementaryAudioDescriptor::new(0x06, &data).unwrap();
+        assert_eq!(MixType::Supplementary, desc.mix_type());
+        assert_eq!(
+            EditorialClassification::AudioDescription,
+            desc.editorial_classification()
+        );
+        assert_eq!(Some("cym"), desc.language_code_str());
+        assert_eq!(&[0xAA], desc.private_data());
+        assert!(SupplementaryAudioDescriptor::new(0x06, &data[..3]).is_err());
+
+        // broadcast-mix spoken subtitles, without language code
+        let desc = SupplementaryAudioDescriptor::new(0x06, &[0b1000_1100]).unwrap();
+        assert_eq!(MixType::Independent, desc.mix_type());
+        assert_eq!(
+            EditorialClassification::SpokenSubtitles,
+            desc.editorial_classification()
+        );
+        assert_eq!(None, desc.language_code());
+        assert!(desc.private_data().is_empty());
+        assert!(SupplementaryAudioDescriptor::new(0x06, &[]).is_err());
+
+        let desc = SupplementaryAudioDescriptor::new(0x06, &[0b0101_1100]).unwrap();
+        assert_eq!(
+            EditorialClassification::UnspecifiedSupplementary,
+            desc.editorial_classification()
+        );
+        let desc = SupplementaryAudioDescriptor::new(0x06, &[0b0110_0000]).unwrap();
+        assert_eq!(
+            EditorialClassification::UserDefined(0x18),
+            desc.editorial_classification()
+        );
+    }
+
+    #[test]
+    fn audio_preselection_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            0b0001_0000, // 2 preselections
+            // preselection 1: stereo, audio description, language, label and 2 aux components
+            0b0000_1001, 0b1000_1110, b'e', b'n', b'g', 0x05, 0b0100_0000, 0x21, 0x22,
+            // preselection 2: 3D, dialogue enhancement and interactivity, with future extension
+            0b0001_0011, 0b0011_0001, 0x01, 0xAA,
+        ];
+        let desc = AudioPreselectionDescriptor::new(0x19, &data).unwrap();
+        let pres: Vec<_> = desc.preselections().collect();
+        assert_eq!(2, pres.len());
+
+        assert_eq!(1, pres[0].preselection_id());
+        assert_eq!(
+            AudioRenderingIndication::Stereo,
+            pres[0].audio_rendering_indication()
+        );
+        assert!(pres[0].audio_description());
+        assert!(!pres[0].spoken_subtitles());
+        assert!(!pres[0].interactivity_enabled());
+        assert_eq!(Some("eng"), pres[0].language_code_str());
+        assert_eq!(Some(5), pres[0].message_id());
+        assert_eq!(&[0x21, 0x22], pres[0].aux_component_tags());
+        assert_eq!(None, pres[0].future_extension());
+
+        assert_eq!(2, pres[1].preselection_id());
+        assert_eq!(
+            AudioRenderingIndication::ThreeDimensional,
+            pres[1].audio_rendering_indication()
+        );
+        assert!(pres[1].dialogue_enhancement());
+        assert!(pres[1].interactivity_enabled());
+        assert_eq!(None, pres[1].language_code());
+        assert_eq!(None, pres[1].message_id());
+        assert!(pres[1].aux_component_tags().is_empty());
+        assert_eq!(Some(&[0xAA][..]), pres[1].future_extension());
+
+        assert!(AudioPreselectionDescriptor::new(0x19, &data[..data.len() - 1]).is_err());
+        assert!(AudioPreselectionDescriptor::new(0x19, &data[..8]).is_err());
+    }
+}
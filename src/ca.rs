@@ -0,0 +1,278 @@
+//! _Conditional Access_ signalling: the _CA_ (tag 0x09), _CA Identifier_ (tag 0x53) and
+//! _Scrambling_ (tag 0x65) descriptors, plus a registry of `CA_system_id` allocations.
+use mpeg2ts_reader::{descriptor, packet};
+use std::fmt;
+
+/// `CA_system_id` ranges allocated by the DVB Project, as listed in _ETSI TS 101 162_.
+const CA_SYSTEM_IDS: &[(u16, u16, &str)] = &[
+    (0x0001, 0x00FF, "Standardized systems"),
+    (0x0100, 0x01FF, "Canal Plus (Mediaguard)"),
+    (0x0200, 0x02FF, "CCETT"),
+    (0x0300, 0x03FF, "Kabel Deutschland"),
+    (0x0400, 0x04FF, "Eurodec"),
+    (0x0500, 0x05FF, "Viaccess"),
+    (0x0600, 0x06FF, "Irdeto"),
+    (0x0700, 0x07FF, "DigiCipher"),
+    (0x0800, 0x08FF, "Matra Communication"),
+    (0x0900, 0x09FF, "NDS (Videoguard)"),
+    (0x0A00, 0x0AFF, "Nokia"),
+    (0x0B00, 0x0BFF, "Conax"),
+    (0x0C00, 0x0CFF, "NTL"),
+    (0x0D00, 0x0DFF, "Cryptoworks"),
+    (0x0E00, 0x0EFF, "PowerVu"),
+    (0x0F00, 0x0FFF, "Sony"),
+    (0x1000, 0x10FF, "Tandberg Television"),
+    (0x1100, 0x11FF, "Thomson"),
+    (0x1200, 0x12FF, "TV/Com"),
+    (0x1300, 0x13FF, "HPT Croatian Post and Telecommunications"),
+    (0x1400, 0x14FF, "HRT Croatian Radio and Television"),
+    (0x1500, 0x15FF, "IBM"),
+    (0x1600, 0x16FF, "Nera"),
+    (0x1700, 0x17FF, "BetaCrypt"),
+    (0x1800, 0x18FF, "Nagravision"),
+    (0x1900, 0x19FF, "Titan Information Systems"),
+    (0x2000, 0x20FF, "Telefonica Servicios Audiovisuales"),
+    (0x2100, 0x21FF, "STOKO"),
+    (0x2200, 0x22FF, "Scopus"),
+    (0x2300, 0x23FF, "BARCO AS"),
+    (0x2400, 0x24FF, "StarGuide Digital Networks"),
+    (0x2500, 0x25FF, "Mentor Data System"),
+    (0x2600, 0x26FF, "BISS"),
+    (0x4700, 0x47FF, "General Instrument"),
+    (0x4800, 0x48FF, "Accessgate"),
+    (0x4900, 0x49FF, "China Digital TV Industry Alliance"),
+    (0x4A10, 0x4A1F, "Easycas"),
+    (0x4A20, 0x4A2F, "AlphaCrypt"),
+    (0x4A30, 0x4A3F, "DVN Holdings"),
+    (0x4A60, 0x4A6F, "SkyCrypt"),
+    (0x4A70, 0x4A7F, "DreamCrypt"),
+    (0x4AD0, 0x4AD1, "XCrypt"),
+    (0x4AE0, 0x4AE1, "DRE-Crypt"),
+    (0x5601, 0x5604, "Verimatrix"),
+];
+
+/// The name of the conditional access system (usually the vendor or product name) to which the
+/// given `CA_system_id` is allocated, or `None` if the value is not in this crate's registry.
+pub fn ca_system_name(ca_system_id: u16) -> Option<&'static str> {
+    CA_SYSTEM_IDS
+        .iter()
+        .find(|(first, last, _)| (*first..=*last).contains(&ca_system_id))
+        .map(|(_, _, name)| *name)
+}
+
+/// Gives the PID carrying ECMs (when found in the PMT) or EMMs (when found in the CAT) for a
+/// particular conditional access system, as defined in _ISO/IEC 13818-1_ section 2.6.16.
+pub struct CaDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> CaDescriptor<'buf> {
+    pub const TAG: u8 = 0x09;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<CaDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 4 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 4,
+            });
+        }
+        Ok(CaDescriptor { data })
+    }
+
+    pub fn ca_system_id(&self) -> u16 {
+        u16::from(self.data[0]) << 8 | u16::from(self.data[1])
+    }
+
+    /// See [`ca_system_name()`](fn.ca_system_name.html)
+    pub fn ca_system_name(&self) -> Option<&'static str> {
+        ca_system_name(self.ca_system_id())
+    }
+
+    pub fn ca_pid(&self) -> packet::Pid {
+        packet::Pid::new(u16::from(self.data[2] & 0b1_1111) << 8 | u16::from(self.data[3]))
+    }
+
+    /// Data whose meaning is defined by the conditional access system
+    pub fn private_data(&self) -> &'buf [u8] {
+        &self.data[4..]
+    }
+}
+impl<'buf> fmt::Debug for CaDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("CaDescriptor")
+            .field("ca_system_id", &self.ca_system_id())
+            .field("ca_system_name", &self.ca_system_name())
+            .field("ca_pid", &self.ca_pid())
+            .field("private_data", &self.private_data())
+            .finish()
+    }
+}
+
+/// Lists the conditional access systems used to scramble a bouquet, service or event, as
+/// defined in _ETSI EN 300 468_ section 6.2.5.
+pub struct CaIdentifierDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> CaIdentifierDescriptor<'buf> {
+    pub const TAG: u8 = 0x53;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<CaIdentifierDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(CaIdentifierDescriptor { data })
+    }
+
+    pub fn ca_system_ids(&self) -> impl Iterator<Item = u16> + 'buf {
+        self.data
+            .chunks_exact(2)
+            .map(|d| u16::from(d[0]) << 8 | u16::from(d[1]))
+    }
+}
+impl<'buf> fmt::Debug for CaIdentifierDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("CaIdentifierDescriptor")
+            .field("ca_system_ids", &self.ca_system_ids().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The scrambling algorithm in use, as defined in _ETSI EN 300 468_ table 81.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScramblingMode {
+    Reserved(u8),
+    DvbCsa1,
+    DvbCsa2,
+    /// DVB-CSA3 in standard mode
+    DvbCsa3Standard,
+    /// DVB-CSA3 in minimally enhanced mode
+    DvbCsa3MinimallyEnhanced,
+    /// DVB-CSA3 in fully enhanced mode
+    DvbCsa3FullyEnhanced,
+    /// DVB-CISSA (AES-128 in CBC mode) with the given version number
+    DvbCissa(u8),
+    /// ATIS IIF Default Scrambling Algorithm
+    AtisIdsa,
+    UserDefined(u8),
+}
+impl ScramblingMode {
+    pub fn from_id(id: u8) -> ScramblingMode {
+        match id {
+            0x01 => ScramblingMode::DvbCsa1,
+            0x02 => ScramblingMode::DvbCsa2,
+            0x03 => ScramblingMode::DvbCsa3Standard,
+            0x04 => ScramblingMode::DvbCsa3MinimallyEnhanced,
+            0x05 => ScramblingMode::DvbCsa3FullyEnhanced,
+            0x10 => ScramblingMode::DvbCissa(1),
+            0x11..=0x1F => ScramblingMode::Reserved(id),
+            0x70 => ScramblingMode::AtisIdsa,
+            0x80..=0xFE => ScramblingMode::UserDefined(id),
+            _ => ScramblingMode::Reserved(id),
+        }
+    }
+
+    /// A short name for the scrambling algorithm, or `None` for reserved and user defined
+    /// values.
+    pub fn algorithm_name(&self) -> Option<&'static str> {
+        match self {
+            ScramblingMode::DvbCsa1 => Some("CSA1"),
+            ScramblingMode::DvbCsa2 => Some("CSA2"),
+            ScramblingMode::DvbCsa3Standard
+            | ScramblingMode::DvbCsa3MinimallyEnhanced
+            | ScramblingMode::DvbCsa3FullyEnhanced => Some("CSA3"),
+            ScramblingMode::DvbCissa(_) => Some("AES"),
+            ScramblingMode::AtisIdsa => Some("ATIS IDSA"),
+            ScramblingMode::Reserved(_) | ScramblingMode::UserDefined(_) => None,
+        }
+    }
+}
+
+/// Indicates the scrambling algorithm used on a service or elementary stream, as defined in
+/// _ETSI EN 300 468_ section 6.2.32.  When absent, DVB-CSA1 or DVB-CSA2 is assumed.
+pub struct ScramblingDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ScramblingDescriptor<'buf> {
+    pub const TAG: u8 = 0x65;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<ScramblingDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 1,
+            });
+        }
+        Ok(ScramblingDescriptor { data })
+    }
+
+    pub fn scrambling_mode(&self) -> ScramblingMode {
+        ScramblingMode::from_id(self.data[0])
+    }
+}
+impl<'buf> fmt::Debug for ScramblingDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ScramblingDescriptor")
+            .field("scrambling_mode", &self.scrambling_mode())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        assert_eq!(Some("Nagravision"), ca_system_name(0x1802));
+        assert_eq!(Some("Irdeto"), ca_system_name(0x0604));
+        assert_eq!(Some("Conax"), ca_system_name(0x0B00));
+        assert_eq!(Some("Viaccess"), ca_system_name(0x0500));
+        assert_eq!(Some("Verimatrix"), ca_system_name(0x5601));
+        assert_eq!(None, ca_system_name(0x0000));
+        assert_eq!(None, ca_system_name(0x5605));
+    }
+
+    #[test]
+    fn ca_descriptor() {
+        let data = [0x09, 0x63, 0xE1, 0x23, 0xAA, 0xBB];
+        let desc = CaDescriptor::new(0x09, &data).unwrap();
+        assert_eq!(0x0963, desc.ca_system_id());
+        assert_eq!(Some("NDS (Videoguard)"), desc.ca_system_name());
+        assert_eq!(packet::Pid::new(0x0123), desc.ca_pid());
+        assert_eq!(&[0xAA, 0xBB], desc.private_data());
+    }
+
+    #[test]
+    fn ca_identifier_descriptor() {
+        let data = [0x18, 0x02, 0x0B, 0x00];
+        let desc = CaIdentifierDescriptor::new(0x53, &data).unwrap();
+        assert_eq!(
+            vec![0x1802, 0x0B00],
+            desc.ca_system_ids().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn scrambling_descriptor() {
+        let desc = ScramblingDescriptor::new(0x65, &[0x10]).unwrap();
+        assert_eq!(ScramblingMode::DvbCissa(1), desc.scrambling_mode());
+        assert_eq!(Some("AES"), desc.scrambling_mode().algorithm_name());
+        assert_eq!(Some("CSA3"), ScramblingMode::from_id(0x04).algorithm_name());
+        assert_eq!(
+            Some("ATIS IDSA"),
+            ScramblingMode::from_id(0x70).algorithm_name()
+        );
+        assert_eq!(None, ScramblingMode::from_id(0x81).algorithm_name());
+        assert!(ScramblingDescriptor::new(0x65, &[]).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! _Component Descriptor_ (tag 0x50) and _Stream Identifier Descriptor_ (tag 0x52), and
+//! [`ComponentStreams`] for linking the two together.
+//!
+//! An EIT event describes its components (video, audio tracks, subtitles etc.) using component
+//! descriptors, but these do not say which elementary stream carries each component.  Instead,
+//! each component has a `component_tag`, and the PMT identifies the elementary stream with a
+//! matching tag using a stream identifier descriptor.
+use crate::eit::Event;
+use crate::{En300_468Descriptors, Text, TextError};
+use mpeg2ts_reader::{descriptor, packet, psi, StreamType};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Identifies and describes a component of an event or service, as defined in _ETSI EN 300 468_
+/// section 6.2.8.
+pub struct ComponentDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ComponentDescriptor<'buf> {
+    pub const TAG: u8 = 0x50;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<ComponentDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 6 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 6,
+            });
+        }
+        Ok(ComponentDescriptor { data })
+    }
+
+    /// Extends `stream_content` for values of `stream_content` `0x9` and above; `0xF` otherwise.
+    pub fn stream_content_ext(&self) -> u8 {
+        self.data[0] >> 4
+    }
+
+    /// The kind of stream, e.g. `0x1` for MPEG-2 video, `0x2` for MPEG-1 Layer 2 audio, `0x3` for
+    /// EBU Teletext / DVB subtitles.
+    pub fn stream_content(&self) -> u8 {
+        self.data[0] & 0b1111
+    }
+
+    /// Interpreted according to `stream_content` (and `stream_content_ext`), as given by
+    /// table 26 of _ETSI EN 300 468_.
+    pub fn component_type(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// Matches the `component_tag` of the [`StreamIdentifierDescriptor`] of the elementary stream
+    /// carrying this component.
+    pub fn component_tag(&self) -> u8 {
+        self.data[2]
+    }
+
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[3..6]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn text(&self) -> Result<Text<'buf>, TextError> {
+        Text::new(&self.data[6..])
+    }
+
+    /// `true` for the MPEG-1 Layer 2 and HE-AAC audio `component_type` values denoting audio
+    /// description for the visually impaired (either ready-mixed, or to be mixed by the receiver).
+    pub fn is_audio_description(&self) -> bool {
+        matches!(
+            (self.stream_content(), self.component_type()),
+            (0x2, 0x40 | 0x47 | 0x48) | (0x6, 0x40 | 0x44 | 0x47..=0x4A)
+        )
+    }
+}
+impl<'buf> fmt::Debug for ComponentDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ComponentDescriptor")
+            .field("stream_content_ext", &self.stream_content_ext())
+            .field("stream_content", &self.stream_content())
+            .field("component_type", &self.component_type())
+            .field("component_tag", &self.component_tag())
+            .field("language_code", &self.language_code_str())
+            .field("text", &self.text())
+            .finish()
+    }
+}
+
+/// Labels an elementary stream in the PMT so that it can be referred to by component
+/// descriptors, as defined in _ETSI EN 300 468_ section 6.2.39.
+pub struct StreamIdentifierDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> StreamIdentifierDescriptor<'buf> {
+    pub const TAG: u8 = 0x52;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<StreamIdentifierDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        Ok(StreamIdentifierDescriptor { data })
+    }
+
+    pub fn component_tag(&self) -> u8 {
+        self.data[0]
+    }
+}
+impl<'buf> fmt::Debug for StreamIdentifierDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("StreamIdentifierDescriptor")
+            .field("component_tag", &self.component_tag())
+            .finish()
+    }
+}
+
+/// An elementary stream from the PMT that has been labelled with a `component_tag`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ComponentStream {
+    pub component_tag: u8,
+    pub elementary_pid: packet::Pid,
+    pub stream_type: StreamType,
+}
+
+/// A component of an event, together with the elementary stream carrying it (if the PMT
+/// identifies one).
+#[derive(Debug)]
+pub struct EventComponent<'buf> {
+    pub component: ComponentDescriptor<'buf>,
+    pub stream: Option<ComponentStream>,
+}
+
+/// Records the `component_tag` of each elementary stream of a single program, so that the
+/// components described by the EIT events of the corresponding service can be located.
+///
+/// The elementary streams are normally supplied as they are announced to the
+/// `demultiplex::DemuxContext` implementation via `FilterRequest::ByStream { stream_info, .. }`.
+/// Since `component_tag` values are only unique within a program, applications handling multiple
+/// programs should keep one `ComponentStreams` instance per program.
+#[derive(Default, Debug)]
+pub struct ComponentStreams {
+    streams: HashMap<u8, ComponentStream>,
+}
+impl ComponentStreams {
+    /// Record the given elementary stream, if its descriptors include a
+    /// [`StreamIdentifierDescriptor`].
+    pub fn add_stream(&mut self, stream_info: &psi::pmt::StreamInfo<'_>) {
+        self.add(
+            stream_info.elementary_pid(),
+            stream_info.stream_type(),
+            stream_info.descriptors::<En300_468Descriptors<'_>>(),
+        )
+    }
+
+    fn add<'buf>(
+        &mut self,
+        elementary_pid: packet::Pid,
+        stream_type: StreamType,
+        descriptors: impl Iterator<
+            Item = Result<En300_468Descriptors<'buf>, descriptor::DescriptorError>,
+        >,
+    ) {
+        for desc in descriptors.flatten() {
+            if let En300_468Descriptors::StreamIdentifier(d) = desc {
+                self.streams.insert(
+                    d.component_tag(),
+                    ComponentStream {
+                        component_tag: d.component_tag(),
+                        elementary_pid,
+                        stream_type,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Forget all streams, for example because a new version of the PMT has been received.
+    pub fn clear(&mut self) {
+        self.streams.clear();
+    }
+
+    pub fn stream(&self, component_tag: u8) -> Option<ComponentStream> {
+        self.streams.get(&component_tag).copied()
+    }
+
+    /// The components described by the given event's [`ComponentDescriptor`]s, each paired with
+    /// the elementary stream carrying it.
+    pub fn event_components<'a, 'buf: 'a>(
+        &'a self,
+        event: &Event<'buf>,
+    ) -> impl Iterator<Item = EventComponent<'buf>> + 'a {
+        event
+            .descriptors::<En300_468Descriptors<'buf>>()
+            .filter_map(|d| match d {
+                Ok(En300_468Descriptors::Component(d)) => Some(d),
+                _ => None,
+            })
+            .map(move |component| EventComponent {
+                stream: self.stream(component.component_tag()),
+                component,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::eit::EitSection;
+
+    #[test]
+    fn component_descriptor() {
+        let data = [0xF2, 0x48, 0x03, b'e', b'n', b'g', b'A', b'D'];
+        let desc = ComponentDescriptor::new(0x50, &data).unwrap();
+        assert_eq!(0xF, desc.stream_content_ext());
+        assert_eq!(0x2, desc.stream_content());
+        assert_eq!(0x48, desc.component_type());
+        assert_eq!(0x03, desc.component_tag());
+        assert_eq!(Some("eng"), desc.language_code_str());
+        assert_eq!("AD", desc.text().unwrap().to_string().unwrap());
+        assert!(desc.is_audio_description());
+        assert!(ComponentDescriptor::new(0x50, &data[..5]).is_err());
+    }
+
+    #[test]
+    fn aac_audio_description() {
+        let ad_types = [0x40, 0x44, 0x47, 0x48, 0x49, 0x4A];
+        for component_type in 0x00..=0xFF {
+            let data = [0xF6, component_type, 0x01, b'e', b'n', b'g'];
+            let desc = ComponentDescriptor::new(0x50, &data).unwrap();
+            assert_eq!(
+                ad_types.contains(&component_type),
+                desc.is_audio_description(),
+                "component_type {:#x}",
+                component_type
+            );
+        }
+    }
+
+    #[test]
+    fn event_components() {
+        let mut streams = ComponentStreams::default();
+        streams.add(
+            packet::Pid::new(0x65),
+            StreamType(0x02),
+            descriptor::DescriptorIter::new(&[0x52, 0x01, 0x01]),
+        );
+        streams.add(
+            packet::Pid::new(0x66),
+            StreamType(0x04),
+            descriptor::DescriptorIter::new(&[
+                0x0A, 0x04, b'e', b'n', b'g', 0x00, 0x52, 0x01, 0x03,
+            ]),
+        );
+        // no stream_identifier_descriptor, so can't be referenced
+        streams.add(
+            packet::Pid::new(0x67),
+            StreamType(0x04),
+            descriptor::DescriptorIter::new(&[]),
+        );
+        assert_eq!(
+            Some(packet::Pid::new(0x66)),
+            streams.stream(0x03).map(|s| s.elementary_pid)
+        );
+
+        #[rustfmt::skip]
+        let section = [
+            0x00, 0x01, 0x00, 0x02, 0x00, 0x4E, // transport_stream_id .. last_table_id
+            0x00, 0x01, // event_id
+            0xC0, 0x79, 0x12, 0x30, 0x45, // start_time
+            0x00, 0x30, 0x00, // duration
+            0x80, 0x1A, // running_status, descriptors_loop_length=26
+            0x50, 0x06, 0xF1, 0x01, 0x01, b'e', b'n', b'g',
+            0x50, 0x06, 0xF2, 0x03, 0x02, b'e', b'n', b'g',
+            0x50, 0x08, 0xF2, 0x48, 0x03, b'e', b'n', b'g', b'A', b'D',
+        ];
+        let sect = EitSection::new(&section);
+        let event = sect.events().next().unwrap().unwrap();
+        let components: Vec<_> = streams.event_components(&event).collect();
+        assert_eq!(3, components.len());
+        assert_eq!(
+            Some(packet::Pid::new(0x65)),
+            components[0].stream.map(|s| s.elementary_pid)
+        );
+        assert_eq!(None, components[1].stream);
+        let ad = components
+            .iter()
+            .find(|c| c.component.is_audio_description())
+            .unwrap();
+        assert_eq!(
+            Some(packet::Pid::new(0x66)),
+            ad.stream.map(|s| s.elementary_pid)
+        );
+    }
+}
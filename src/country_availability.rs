@@ -0,0 +1,115 @@
+//! _Country Availability Descriptor_ (tag 0x49)
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Lists countries in which reception of a service is (or is not) intended, as defined in
+/// _ETSI EN 300 468_ section 6.2.10.
+pub struct CountryAvailabilityDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> CountryAvailabilityDescriptor<'buf> {
+    pub const TAG: u8 = 0x49;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<CountryAvailabilityDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        Ok(CountryAvailabilityDescriptor { data })
+    }
+
+    /// If `true`, reception of the service is intended in the listed countries; if `false`,
+    /// reception is intended everywhere except the listed countries.
+    pub fn country_availability_flag(&self) -> bool {
+        self.data[0] & 0b1000_0000 != 0
+    }
+
+    /// Three-character ISO 3166 country codes
+    pub fn country_codes(&self) -> impl Iterator<Item = &'buf [u8]> {
+        self.data[1..].chunks_exact(3)
+    }
+
+    /// Three-character ISO 3166 country codes as strings, or `None` for any code that is not
+    /// valid UTF-8
+    pub fn country_codes_str(&self) -> impl Iterator<Item = Option<&'buf str>> {
+        self.country_codes().map(|c| std::str::from_utf8(c).ok())
+    }
+
+    fn lists(&self, country_code: &str) -> bool {
+        self.country_codes()
+            .any(|c| c.eq_ignore_ascii_case(country_code.as_bytes()))
+    }
+}
+impl<'buf> fmt::Debug for CountryAvailabilityDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("CountryAvailabilityDescriptor")
+            .field(
+                "country_availability_flag",
+                &self.country_availability_flag(),
+            )
+            .field(
+                "country_codes",
+                &self.country_codes_str().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Evaluates all the [`CountryAvailabilityDescriptor`] instances from a single descriptor loop
+/// to decide if the service should be offered to viewers in the given country (a three-character
+/// ISO 3166 code, compared case-insensitively).
+///
+/// - A country listed by any descriptor with `country_availability_flag` unset is excluded.
+/// - Otherwise, if any descriptor has `country_availability_flag` set, the country must be
+///   listed by one of those descriptors.
+/// - Otherwise (including when there are no descriptors at all) the service is available.
+pub fn is_available<'a, 'buf: 'a>(
+    descriptors: impl IntoIterator<Item = &'a CountryAvailabilityDescriptor<'buf>>,
+    country_code: &str,
+) -> bool {
+    crate::is_included(
+        descriptors
+            .into_iter()
+            .map(|desc| (desc.country_availability_flag(), desc.lists(country_code))),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn country_availability_descriptor() {
+        let data = [0x80, b'G', b'B', b'R', b'I', b'R', b'L'];
+        let desc = CountryAvailabilityDescriptor::new(0x49, &data).unwrap();
+        assert!(desc.country_availability_flag());
+        assert_eq!(
+            vec![Some("GBR"), Some("IRL")],
+            desc.country_codes_str().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn availability() {
+        let include = [0xFF, b'D', b'E', b'U', b'A', b'U', b'T', b'C', b'H', b'E'];
+        let include = CountryAvailabilityDescriptor::new(0x49, &include).unwrap();
+        let exclude = [0x7F, b'C', b'H', b'E'];
+        let exclude = CountryAvailabilityDescriptor::new(0x49, &exclude).unwrap();
+
+        assert!(is_available(std::iter::empty(), "GBR"));
+        assert!(is_available([&include], "aut"));
+        assert!(!is_available([&include], "GBR"));
+        assert!(is_available([&include, &exclude], "DEU"));
+        assert!(!is_available([&include, &exclude], "CHE"));
+        assert!(!is_available([&include, &exclude], "GBR"));
+        assert!(is_available([&exclude], "GBR"));
+        assert!(!is_available([&exclude], "CHE"));
+    }
+}
@@ -0,0 +1,319 @@
+//! _Content Identifier Descriptor_ (tag 0x76) and _Default Authority Descriptor_ (tag 0x73),
+//! carrying TV-Anytime _Content Reference Identifiers_ (CRIDs) as specified by _ETSI TS 102 323_.
+//!
+//! CRIDs are usually broadcast without their authority part (e.g. `/ABC123`), in which case the
+//! authority is given by the default authority descriptor found at the most specific level of
+//! event, service, transport stream or network.  See [`default_authority()`].
+use crate::En300_468Descriptors;
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Names the authority of any CRIDs given in relative form, as defined in _ETSI TS 102 323_
+/// section 5.2.2.
+pub struct DefaultAuthorityDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> DefaultAuthorityDescriptor<'buf> {
+    pub const TAG: u8 = 0x73;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<DefaultAuthorityDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(DefaultAuthorityDescriptor { data })
+    }
+
+    /// The authority name, e.g. `fp.bbc.co.uk`
+    pub fn default_authority(&self) -> &'buf [u8] {
+        self.data
+    }
+
+    /// The authority name as a string, or `None` if the bytes are not valid UTF-8
+    pub fn default_authority_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.data).ok()
+    }
+}
+impl<'buf> fmt::Debug for DefaultAuthorityDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DefaultAuthorityDescriptor")
+            .field("default_authority", &self.default_authority_str())
+            .finish()
+    }
+}
+
+/// Finds the default authority applicable to the CRIDs of a descriptor loop.
+///
+/// Pass the event's descriptor loop (from the EIT), followed by those of the service (from the
+/// SDT), the transport stream (from the NIT transport stream loop) and the network; the first
+/// `DefaultAuthorityDescriptor` found is returned.
+pub fn default_authority<'buf, L>(loops: L) -> Option<DefaultAuthorityDescriptor<'buf>>
+where
+    L: IntoIterator,
+    L::Item: IntoIterator<Item = Result<En300_468Descriptors<'buf>, descriptor::DescriptorError>>,
+{
+    crate::find_in_loops(loops, |d| match d {
+        En300_468Descriptors::DefaultAuthority(d) => Some(d),
+        _ => None,
+    })
+}
+
+/// What the content referred to by a [`Crid`] is
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CridType {
+    /// No type is defined
+    Unspecified,
+    /// Identifies a single item of content (an episode, film, etc.)
+    Programme,
+    /// Identifies a group of items of content, such as a series
+    Series,
+    /// Identifies content recommended in connection with this event
+    Recommendation,
+    Reserved(u8),
+    UserDefined(u8),
+}
+impl CridType {
+    /// Note that the UK _D-Book_ uses the user-defined values `0x31` to `0x33` rather than `0x01`
+    /// to `0x03`, so these are treated equivalently.
+    pub fn from_id(id: u8) -> CridType {
+        match id {
+            0x00 => CridType::Unspecified,
+            0x01 | 0x31 => CridType::Programme,
+            0x02 | 0x32 => CridType::Series,
+            0x03 | 0x33 => CridType::Recommendation,
+            0x04..=0x1F => CridType::Reserved(id),
+            0x20..=0x3F => CridType::UserDefined(id),
+            _ => panic!("Invalid crid_type value {} (must be less than 64)", id),
+        }
+    }
+}
+
+/// Where the CRID value of a [`Crid`] is to be found
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CridLocation<'buf> {
+    /// The CRID is given inline
+    Inline(&'buf [u8]),
+    /// The CRID is given in the _Content Identifier Table_, by the entry with the given
+    /// `crid_ref`
+    Cit {
+        crid_ref: u16,
+    },
+    Reserved(u8),
+}
+
+/// One entry from a [`ContentIdentifierDescriptor`]
+pub struct Crid<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> Crid<'buf> {
+    pub fn crid_type(&self) -> CridType {
+        CridType::from_id(self.data[0] >> 2)
+    }
+
+    pub fn crid_location(&self) -> CridLocation<'buf> {
+        match self.data[0] & 0b11 {
+            0 => CridLocation::Inline(&self.data[2..]),
+            1 => CridLocation::Cit {
+                crid_ref: u16::from(self.data[1]) << 8 | u16::from(self.data[2]),
+            },
+            v => CridLocation::Reserved(v),
+        }
+    }
+
+    /// The absolute `crid://` URI for an inline CRID, using the given default authority (see
+    /// [`default_authority()`]) if the CRID is relative.
+    ///
+    /// Returns `None` if the CRID is not given inline, if it is relative but no default authority
+    /// is available, or if it is not valid UTF-8.
+    pub fn to_uri(
+        &self,
+        default_authority: Option<&DefaultAuthorityDescriptor<'_>>,
+    ) -> Option<String> {
+        let crid = match self.crid_location() {
+            CridLocation::Inline(crid) => std::str::from_utf8(crid).ok()?,
+            _ => return None,
+        };
+        if crid.starts_with('/') {
+            let authority = default_authority?.default_authority_str()?;
+            Some(format!("crid://{}{}", authority, crid))
+        } else if crid
+            .get(..7)
+            .map_or(false, |scheme| scheme.eq_ignore_ascii_case("crid://"))
+        {
+            Some(crid.to_string())
+        } else {
+            Some(format!("crid://{}", crid))
+        }
+    }
+}
+impl<'buf> fmt::Debug for Crid<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut s = f.debug_struct("Crid");
+        s.field("crid_type", &self.crid_type());
+        match self.crid_location() {
+            CridLocation::Inline(crid) => s.field("crid", &String::from_utf8_lossy(crid)),
+            loc => s.field("crid_location", &loc),
+        };
+        s.finish()
+    }
+}
+
+struct CridIterator<'buf> {
+    remaining_data: &'buf [u8],
+}
+impl<'buf> Iterator for CridIterator<'buf> {
+    type Item = Crid<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_data.is_empty() {
+            return None;
+        }
+        // lengths were checked by ContentIdentifierDescriptor::new()
+        let len = crid_entry_len(self.remaining_data).unwrap();
+        let (head, tail) = self.remaining_data.split_at(len);
+        self.remaining_data = tail;
+        Some(Crid { data: head })
+    }
+}
+
+/// The size of the CRID entry at the start of `data`, or `Err` giving the number of bytes
+/// required if `data` is too short
+fn crid_entry_len(data: &[u8]) -> Result<usize, usize> {
+    let len = match data[0] & 0b11 {
+        0 => 2 + usize::from(*data.get(1).ok_or(2usize)?),
+        1 => 3,
+        // reserved crid_location values have no defined payload
+        _ => 1,
+    };
+    if len > data.len() {
+        Err(len)
+    } else {
+        Ok(len)
+    }
+}
+
+/// Associates an event with TV-Anytime CRIDs identifying the programme, the series it belongs to
+/// and so on, as defined in _ETSI TS 102 323_ section 12.1.
+pub struct ContentIdentifierDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ContentIdentifierDescriptor<'buf> {
+    pub const TAG: u8 = 0x76;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<ContentIdentifierDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        let mut pos = 0;
+        while pos < data.len() {
+            match crid_entry_len(&data[pos..]) {
+                Ok(len) => pos += len,
+                Err(required) => {
+                    return Err(descriptor::DescriptorError::NotEnoughData {
+                        tag,
+                        actual: data.len(),
+                        expected: pos + required,
+                    })
+                }
+            }
+        }
+        Ok(ContentIdentifierDescriptor { data })
+    }
+
+    pub fn crids(&self) -> impl Iterator<Item = Crid<'buf>> {
+        CridIterator {
+            remaining_data: self.data,
+        }
+    }
+
+    /// The first CRID of the given type
+    pub fn crid(&self, crid_type: CridType) -> Option<Crid<'buf>> {
+        self.crids().find(|c| c.crid_type() == crid_type)
+    }
+}
+impl<'buf> fmt::Debug for ContentIdentifierDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ContentIdentifierDescriptor")
+            .field("crids", &self.crids().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[rustfmt::skip]
+    const DATA: [u8; 20] = [
+        0x31 << 2, 0x07, b'/', b'E', b'P', b'1', b'2', b'3', b'4',
+        0x32 << 2, 0x05, b'/', b'S', b'E', b'R', b'1',
+        0x03 << 2 | 1, 0x12, 0x34,
+        0x02 << 2 | 2,
+    ];
+
+    #[test]
+    fn content_identifier_descriptor() {
+        let desc = ContentIdentifierDescriptor::new(0x76, &DATA).unwrap();
+        let crids: Vec<_> = desc.crids().collect();
+        assert_eq!(4, crids.len());
+        assert_eq!(CridType::Programme, crids[0].crid_type());
+        assert_eq!(CridLocation::Inline(b"/EP1234"), crids[0].crid_location());
+        assert_eq!(CridType::Series, crids[1].crid_type());
+        assert_eq!(CridType::Recommendation, crids[2].crid_type());
+        assert_eq!(
+            CridLocation::Cit { crid_ref: 0x1234 },
+            crids[2].crid_location()
+        );
+        assert_eq!(CridLocation::Reserved(2), crids[3].crid_location());
+        let expected = |len| match ContentIdentifierDescriptor::new(0x76, &DATA[..len]) {
+            Err(descriptor::DescriptorError::NotEnoughData { expected, .. }) => expected,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(9, expected(8));
+        assert_eq!(11, expected(10));
+        assert_eq!(19, expected(18));
+    }
+
+    #[test]
+    fn crid_uri() {
+        let desc = ContentIdentifierDescriptor::new(0x76, &DATA).unwrap();
+        let programme = desc.crid(CridType::Programme).unwrap();
+        assert_eq!(None, programme.to_uri(None));
+
+        // event-level loop has no default authority, so the service-level one is used
+        let event_loop = [0x76, 0x03, 0x01 << 2 | 1, 0x00, 0x01];
+        let service_loop = [
+            0x73, 0x0C, b'f', b'p', b'.', b'b', b'b', b'c', b'.', b'c', b'o', b'.', b'u', b'k',
+        ];
+        let network_loop = [0x73, 0x07, b'e', b'x', b'.', b'c', b'o', b'.', b'u', b'k'];
+        let authority = default_authority([
+            descriptor::DescriptorIter::new(&event_loop[..]),
+            descriptor::DescriptorIter::new(&service_loop[..]),
+            descriptor::DescriptorIter::new(&network_loop[..]),
+        ])
+        .unwrap();
+        assert_eq!(Some("fp.bbc.co.uk"), authority.default_authority_str());
+        assert_eq!(
+            Some("crid://fp.bbc.co.uk/EP1234".to_string()),
+            programme.to_uri(Some(&authority))
+        );
+        assert_eq!(
+            None,
+            desc.crid(CridType::Recommendation)
+                .unwrap()
+                .to_uri(Some(&authority))
+        );
+
+        #[rustfmt::skip]
+        let absolute = [
+            0x01 << 2, 0x0D,
+            b'e', b'x', b'.', b'c', b'o', b'.', b'u', b'k', b'/', b'1', b'2', b'3', b'4',
+        ];
+        let desc = ContentIdentifierDescriptor::new(0x76, &absolute).unwrap();
+        assert_eq!(
+            Some("crid://ex.co.uk/1234".to_string()),
+            desc.crids().next().unwrap().to_uri(Some(&authority))
+        );
+    }
+}
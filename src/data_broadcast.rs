@@ -0,0 +1,584 @@
+//! _Data Broadcast Descriptor_ (tag 0x64) and _Data Broadcast Id Descriptor_ (tag 0x66), and
+//! decoding of their selector bytes for the most common `data_broadcast_id` values.
+//!
+//! The `data_broadcast_id` values are registered with the DVB Project (see _ETSI TS 101 162_),
+//! and the format of the selector bytes depends on the value.
+use crate::{Text, TextError};
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Multiprotocol encapsulation, _ETSI EN 301 192_
+pub const MPE: u16 = 0x0005;
+/// DVB data carousel, _ETSI EN 301 192_
+pub const DATA_CAROUSEL: u16 = 0x0006;
+/// DVB object carousel, _ETSI EN 301 192_
+pub const OBJECT_CAROUSEL: u16 = 0x0007;
+/// System software update, _ETSI TS 102 006_
+pub const SSU: u16 = 0x000A;
+/// IP/MAC notification table, _ETSI EN 301 192_
+pub const IP_MAC_NOTIFICATION: u16 = 0x000B;
+/// MHP application signalling, _ETSI TS 102 812_
+pub const MHP: u16 = 0x00F0;
+/// HbbTV application signalling, _ETSI TS 102 796_
+pub const HBBTV: u16 = 0x0123;
+
+fn read_u24(data: &[u8]) -> u32 {
+    u32::from(data[0]) << 16 | u32::from(data[1]) << 8 | u32::from(data[2])
+}
+
+fn read_u32(data: &[u8]) -> u32 {
+    u32::from(data[0]) << 24 | read_u24(&data[1..])
+}
+
+/// Selector for multiprotocol encapsulation
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MpeInfo {
+    /// Number of bytes of the MAC address carried in the section header
+    pub mac_address_range: u8,
+    pub mac_ip_mapping_flag: bool,
+    /// `true` for 32-bit alignment, `false` for 8-bit
+    pub alignment_indicator: bool,
+    pub max_sections_per_datagram: u8,
+}
+
+/// Selector for data and object carousels
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CarouselInfo {
+    /// `1` for a one-layer carousel, `2` for a two-layer carousel
+    pub carousel_type_id: u8,
+    pub transaction_id: u32,
+    /// Timeout in milliseconds for receiving the DSI message
+    pub time_out_value_dsi: u32,
+    /// Timeout in milliseconds for receiving the DII messages
+    pub time_out_value_dii: u32,
+    /// Leak rate in units of 50 bytes per second
+    pub leak_rate: u32,
+}
+impl CarouselInfo {
+    const SIZE: usize = 16;
+
+    fn new(data: &[u8]) -> CarouselInfo {
+        CarouselInfo {
+            carousel_type_id: data[0] >> 6,
+            transaction_id: read_u32(&data[1..]),
+            time_out_value_dsi: read_u32(&data[5..]),
+            time_out_value_dii: read_u32(&data[9..]),
+            leak_rate: read_u24(&data[13..]) & 0x3F_FFFF,
+        }
+    }
+}
+
+/// A name for an object carousel, from [`ObjectCarouselInfo`]
+pub struct ObjectName<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ObjectName<'buf> {
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn object_name(&self) -> Result<Text<'buf>, TextError> {
+        Text::new(&self.data[4..])
+    }
+}
+impl<'buf> fmt::Debug for ObjectName<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ObjectName")
+            .field("language_code", &self.language_code_str())
+            .field("object_name", &self.object_name())
+            .finish()
+    }
+}
+
+/// Selector for object carousels
+pub struct ObjectCarouselInfo<'buf> {
+    pub carousel: CarouselInfo,
+    object_names: &'buf [u8],
+}
+impl<'buf> ObjectCarouselInfo<'buf> {
+    pub fn object_names(&self) -> impl Iterator<Item = ObjectName<'buf>> {
+        let mut remaining = self.object_names;
+        std::iter::from_fn(move || {
+            if remaining.is_empty() {
+                return None;
+            }
+            // lengths were checked by DataBroadcastSelector::new()
+            let (head, tail) = remaining.split_at(4 + usize::from(remaining[3]));
+            remaining = tail;
+            Some(ObjectName { data: head })
+        })
+    }
+}
+impl<'buf> fmt::Debug for ObjectCarouselInfo<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ObjectCarouselInfo")
+            .field("carousel", &self.carousel)
+            .field("object_names", &self.object_names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// One organisation's software updates, from [`SsuInfo`]
+pub struct SsuOui<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> SsuOui<'buf> {
+    /// IEEE _Organizationally Unique Identifier_ of the manufacturer whose receivers the update
+    /// applies to
+    pub fn oui(&self) -> u32 {
+        read_u24(self.data)
+    }
+
+    /// The kind of update mechanism, as given by table 4 of _ETSI TS 102 006_ (e.g. `0x1` for a
+    /// standard update carousel without UNT, `0x2` or `0x3` for a carousel with a UNT)
+    pub fn update_type(&self) -> u8 {
+        self.data[3] & 0b1111
+    }
+
+    /// The version of the update, if versioning is in use
+    pub fn update_version(&self) -> Option<u8> {
+        if self.data[4] & 0b10_0000 != 0 {
+            Some(self.data[4] & 0b1_1111)
+        } else {
+            None
+        }
+    }
+
+    pub fn selector_bytes(&self) -> &'buf [u8] {
+        &self.data[6..]
+    }
+}
+impl<'buf> fmt::Debug for SsuOui<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("SsuOui")
+            .field("oui", &format!("{:06x}", self.oui()))
+            .field("update_type", &self.update_type())
+            .field("update_version", &self.update_version())
+            .finish()
+    }
+}
+
+/// Selector for system software update
+pub struct SsuInfo<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> SsuInfo<'buf> {
+    fn oui_data_length(&self) -> usize {
+        usize::from(self.data[0])
+    }
+
+    pub fn ouis(&self) -> impl Iterator<Item = SsuOui<'buf>> {
+        let mut remaining = &self.data[1..1 + self.oui_data_length()];
+        std::iter::from_fn(move || {
+            if remaining.is_empty() {
+                return None;
+            }
+            // lengths were checked by DataBroadcastSelector::new()
+            let (head, tail) = remaining.split_at(6 + usize::from(remaining[5]));
+            remaining = tail;
+            Some(SsuOui { data: head })
+        })
+    }
+
+    pub fn private_data(&self) -> &'buf [u8] {
+        &self.data[1 + self.oui_data_length()..]
+    }
+}
+impl<'buf> fmt::Debug for SsuInfo<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("SsuInfo")
+            .field("ouis", &self.ouis().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// One IP/MAC platform, from [`IpMacNotificationInfo`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IpMacPlatform {
+    pub platform_id: u32,
+    /// `0x01` if the INT carries IP/MAC stream location information
+    pub action_type: u8,
+    /// The version of the INT, if versioning is in use
+    pub int_version: Option<u8>,
+}
+
+/// Selector for the IP/MAC notification table
+pub struct IpMacNotificationInfo<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> IpMacNotificationInfo<'buf> {
+    fn platform_id_data_length(&self) -> usize {
+        usize::from(self.data[0])
+    }
+
+    pub fn platforms(&self) -> impl Iterator<Item = IpMacPlatform> + 'buf {
+        self.data[1..1 + self.platform_id_data_length()]
+            .chunks_exact(5)
+            .map(|d| IpMacPlatform {
+                platform_id: read_u24(d),
+                action_type: d[3],
+                int_version: if d[4] & 0b10_0000 != 0 {
+                    Some(d[4] & 0b1_1111)
+                } else {
+                    None
+                },
+            })
+    }
+
+    pub fn private_data(&self) -> &'buf [u8] {
+        &self.data[1 + self.platform_id_data_length()..]
+    }
+}
+impl<'buf> fmt::Debug for IpMacNotificationInfo<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("IpMacNotificationInfo")
+            .field("platforms", &self.platforms().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// An application type signalled for HbbTV, with the version of the AIT describing it
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct HbbTvApplication {
+    pub application_type: u16,
+    pub ait_version_number: u8,
+}
+
+/// The meaning of the selector bytes of a [`DataBroadcastDescriptor`] or
+/// [`DataBroadcastIdDescriptor`], as determined by the `data_broadcast_id`.
+#[derive(Debug)]
+pub enum DataBroadcastSelector<'buf> {
+    Mpe(MpeInfo),
+    DataCarousel(CarouselInfo),
+    ObjectCarousel(ObjectCarouselInfo<'buf>),
+    Ssu(SsuInfo<'buf>),
+    IpMacNotification(IpMacNotificationInfo<'buf>),
+    /// The MHP application types announced
+    Mhp(Vec<u16>),
+    HbbTv(Vec<HbbTvApplication>),
+    /// The selector bytes are too short for the structure required by the `data_broadcast_id`
+    Invalid(&'buf [u8]),
+    /// The `data_broadcast_id` is not one for which this crate can decode the selector bytes
+    Other(&'buf [u8]),
+}
+impl<'buf> DataBroadcastSelector<'buf> {
+    pub fn new(data_broadcast_id: u16, selector: &'buf [u8]) -> DataBroadcastSelector<'buf> {
+        Self::decode(data_broadcast_id, selector)
+            .unwrap_or(DataBroadcastSelector::Invalid(selector))
+    }
+
+    fn decode(data_broadcast_id: u16, data: &'buf [u8]) -> Option<DataBroadcastSelector<'buf>> {
+        match data_broadcast_id {
+            MPE => {
+                let d = data.get(..2)?;
+                Some(DataBroadcastSelector::Mpe(MpeInfo {
+                    mac_address_range: d[0] >> 5,
+                    mac_ip_mapping_flag: d[0] & 0b1_0000 != 0,
+                    alignment_indicator: d[0] & 0b1000 != 0,
+                    max_sections_per_datagram: d[1],
+                }))
+            }
+            DATA_CAROUSEL => Some(DataBroadcastSelector::DataCarousel(CarouselInfo::new(
+                data.get(..CarouselInfo::SIZE)?,
+            ))),
+            OBJECT_CAROUSEL => {
+                let carousel = CarouselInfo::new(data.get(..CarouselInfo::SIZE)?);
+                let object_names = &data[CarouselInfo::SIZE..];
+                let mut remaining = object_names;
+                while !remaining.is_empty() {
+                    let len = 4 + usize::from(*remaining.get(3)?);
+                    remaining = remaining.get(len..)?;
+                }
+                Some(DataBroadcastSelector::ObjectCarousel(ObjectCarouselInfo {
+                    carousel,
+                    object_names,
+                }))
+            }
+            SSU => {
+                let oui_data_length = usize::from(*data.first()?);
+                let mut remaining = data.get(1..1 + oui_data_length)?;
+                while !remaining.is_empty() {
+                    let len = 6 + usize::from(*remaining.get(5)?);
+                    remaining = remaining.get(len..)?;
+                }
+                Some(DataBroadcastSelector::Ssu(SsuInfo { data }))
+            }
+            IP_MAC_NOTIFICATION => {
+                let platform_id_data_length = usize::from(*data.first()?);
+                if platform_id_data_length % 5 != 0 {
+                    return None;
+                }
+                data.get(1..1 + platform_id_data_length)?;
+                Some(DataBroadcastSelector::IpMacNotification(
+                    IpMacNotificationInfo { data },
+                ))
+            }
+            MHP => Some(DataBroadcastSelector::Mhp(
+                data.chunks_exact(2)
+                    .map(|d| u16::from(d[0]) << 8 | u16::from(d[1]))
+                    .collect(),
+            )),
+            HBBTV => Some(DataBroadcastSelector::HbbTv(
+                data.chunks_exact(3)
+                    .map(|d| HbbTvApplication {
+                        application_type: u16::from(d[0]) << 8 | u16::from(d[1]),
+                        ait_version_number: d[2] & 0b1_1111,
+                    })
+                    .collect(),
+            )),
+            _ => Some(DataBroadcastSelector::Other(data)),
+        }
+    }
+}
+
+/// Identifies the data broadcast carried by a component of a service, as defined in
+/// _ETSI EN 300 468_ section 6.2.11.
+pub struct DataBroadcastDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> DataBroadcastDescriptor<'buf> {
+    pub const TAG: u8 = 0x64;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<DataBroadcastDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        let not_enough = |expected| descriptor::DescriptorError::NotEnoughData {
+            tag,
+            actual: data.len(),
+            expected,
+        };
+        if data.len() < 4 {
+            return Err(not_enough(4));
+        }
+        let text_length_pos = 4 + usize::from(data[3]) + 3;
+        let text_length = data
+            .get(text_length_pos)
+            .ok_or_else(|| not_enough(text_length_pos + 1))?;
+        let expected = text_length_pos + 1 + usize::from(*text_length);
+        if data.len() < expected {
+            return Err(not_enough(expected));
+        }
+        Ok(DataBroadcastDescriptor { data })
+    }
+
+    pub fn data_broadcast_id(&self) -> u16 {
+        u16::from(self.data[0]) << 8 | u16::from(self.data[1])
+    }
+
+    /// Matches the `component_tag` of the
+    /// [`StreamIdentifierDescriptor`](../component/struct.StreamIdentifierDescriptor.html) of the
+    /// elementary stream carrying the data broadcast
+    pub fn component_tag(&self) -> u8 {
+        self.data[2]
+    }
+
+    fn selector_length(&self) -> usize {
+        usize::from(self.data[3])
+    }
+
+    pub fn selector_bytes(&self) -> &'buf [u8] {
+        &self.data[4..4 + self.selector_length()]
+    }
+
+    pub fn selector(&self) -> DataBroadcastSelector<'buf> {
+        DataBroadcastSelector::new(self.data_broadcast_id(), self.selector_bytes())
+    }
+
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        let start = 4 + self.selector_length();
+        &self.data[start..start + 3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    /// Description of the data broadcast
+    pub fn text(&self) -> Result<Text<'buf>, TextError> {
+        let start = 4 + self.selector_length() + 3;
+        Text::read(&self.data[start..]).map(|(text, _)| text)
+    }
+}
+impl<'buf> fmt::Debug for DataBroadcastDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DataBroadcastDescriptor")
+            .field(
+                "data_broadcast_id",
+                &format!("{:#06x}", self.data_broadcast_id()),
+            )
+            .field("component_tag", &self.component_tag())
+            .field("selector", &self.selector())
+            .field("language_code", &self.language_code_str())
+            .field("text", &self.text())
+            .finish()
+    }
+}
+
+/// Identifies the kind of data broadcast carried by an elementary stream, as defined in
+/// _ETSI EN 300 468_ section 6.2.12.
+pub struct DataBroadcastIdDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> DataBroadcastIdDescriptor<'buf> {
+    pub const TAG: u8 = 0x66;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<DataBroadcastIdDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 2 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 2,
+            });
+        }
+        Ok(DataBroadcastIdDescriptor { data })
+    }
+
+    pub fn data_broadcast_id(&self) -> u16 {
+        u16::from(self.data[0]) << 8 | u16::from(self.data[1])
+    }
+
+    pub fn id_selector_bytes(&self) -> &'buf [u8] {
+        &self.data[2..]
+    }
+
+    pub fn selector(&self) -> DataBroadcastSelector<'buf> {
+        DataBroadcastSelector::new(self.data_broadcast_id(), self.id_selector_bytes())
+    }
+}
+impl<'buf> fmt::Debug for DataBroadcastIdDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DataBroadcastIdDescriptor")
+            .field(
+                "data_broadcast_id",
+                &format!("{:#06x}", self.data_broadcast_id()),
+            )
+            .field("selector", &self.selector())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn data_broadcast_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            0x00, 0x07, // object carousel
+            0x0B, // component_tag
+            0x18, // selector_length
+            0b1000_0000, 0x80, 0x00, 0x00, 0x02, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x0B, 0xB8,
+            0x00, 0x00, 0x00,
+            b'e', b'n', b'g', 0x04, b'A', b'p', b'p', b's',
+            b'e', b'n', b'g',
+            0x04, b'D', b'a', b't', b'a',
+        ];
+        let desc = DataBroadcastDescriptor::new(0x64, &data).unwrap();
+        assert_eq!(OBJECT_CAROUSEL, desc.data_broadcast_id());
+        assert_eq!(0x0B, desc.component_tag());
+        assert_eq!(Some("eng"), desc.language_code_str());
+        assert_eq!("Data", desc.text().unwrap().to_string().unwrap());
+        match desc.selector() {
+            DataBroadcastSelector::ObjectCarousel(info) => {
+                assert_eq!(2, info.carousel.carousel_type_id);
+                assert_eq!(0x8000_0002, info.carousel.transaction_id);
+                assert_eq!(0xFFFF_FFFF, info.carousel.time_out_value_dsi);
+                assert_eq!(3000, info.carousel.time_out_value_dii);
+                let names: Vec<_> = info.object_names().collect();
+                assert_eq!(1, names.len());
+                assert_eq!("Apps", names[0].object_name().unwrap().to_string().unwrap());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(DataBroadcastDescriptor::new(0x64, &data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn ssu_selector() {
+        #[rustfmt::skip]
+        let data = [
+            0x00, 0x0A,
+            0x08, // OUI_data_length
+            0x00, 0x15, 0x0A, 0xF1, 0b1110_0011, 0x02, // OUI, update_type=1, version 3
+            0x99, 0x99,
+            0xAA, // private data
+        ];
+        let desc = DataBroadcastIdDescriptor::new(0x66, &data).unwrap();
+        match desc.selector() {
+            DataBroadcastSelector::Ssu(info) => {
+                let ouis: Vec<_> = info.ouis().collect();
+                assert_eq!(1, ouis.len());
+                assert_eq!(0x00150A, ouis[0].oui());
+                assert_eq!(1, ouis[0].update_type());
+                assert_eq!(Some(3), ouis[0].update_version());
+                assert_eq!(&[0x99, 0x99], ouis[0].selector_bytes());
+                assert_eq!(&[0xAA], info.private_data());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        // OUI_data_length exceeds the selector
+        let desc = DataBroadcastIdDescriptor::new(0x66, &data[..8]).unwrap();
+        assert!(matches!(desc.selector(), DataBroadcastSelector::Invalid(_)));
+    }
+
+    #[test]
+    fn other_selectors() {
+        let selector = DataBroadcastSelector::new(MPE, &[0b1101_1000, 0x01]);
+        assert!(matches!(
+            selector,
+            DataBroadcastSelector::Mpe(MpeInfo {
+                mac_address_range: 6,
+                mac_ip_mapping_flag: true,
+                alignment_indicator: true,
+                max_sections_per_datagram: 1,
+            })
+        ));
+        match DataBroadcastSelector::new(IP_MAC_NOTIFICATION, &[0x05, 0x00, 0x00, 0x01, 0x01, 0x21])
+        {
+            DataBroadcastSelector::IpMacNotification(info) => assert_eq!(
+                vec![IpMacPlatform {
+                    platform_id: 1,
+                    action_type: 1,
+                    int_version: Some(1),
+                }],
+                info.platforms().collect::<Vec<_>>()
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+        match DataBroadcastSelector::new(HBBTV, &[0x00, 0x10, 0x05]) {
+            DataBroadcastSelector::HbbTv(apps) => assert_eq!(
+                vec![HbbTvApplication {
+                    application_type: 0x10,
+                    ait_version_number: 5,
+                }],
+                apps
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+        match DataBroadcastSelector::new(MHP, &[0x00, 0x01, 0x00, 0x02]) {
+            DataBroadcastSelector::Mhp(types) => assert_eq!(vec![1, 2], types),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            DataBroadcastSelector::new(0x0106, &[0x01]),
+            DataBroadcastSelector::Other(&[0x01])
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 //! _Event Information Table_ section data
+use crate::private_data::{PrivateDescriptor, PrivateDescriptorIter};
 use crate::sdt::RunningStatus;
 use crate::time::{MjdTimestamp, MjdTimestampError};
 use crate::ActualOther;
@@ -14,7 +15,7 @@ pub struct BcdDuration {
     raw: u32,
 }
 impl BcdDuration {
-    fn new(raw: u32) -> Self {
+    pub(crate) fn new(raw: u32) -> Self {
         BcdDuration { raw }
     }
 
@@ -108,12 +109,28 @@ impl<'buf> Event<'buf> {
         usize::from(self.data[10] & 0b1111) << 8 | usize::from(self.data[11])
     }
 
+    /// The raw bytes of this event's descriptor loop.
+    pub(crate) fn descriptors_data(&self) -> &'buf [u8] {
+        let start = 12;
+        let end = start + self.descriptors_loop_length();
+        &self.data[start..end]
+    }
+
     pub fn descriptors<Desc: descriptor::Descriptor<'buf>>(
         &self,
     ) -> descriptor::DescriptorIter<'buf, Desc> {
-        let start = 12;
-        let end = start + self.descriptors_loop_length();
-        descriptor::DescriptorIter::new(&self.data[start..end])
+        descriptor::DescriptorIter::new(self.descriptors_data())
+    }
+
+    /// Like [`descriptors()`](#method.descriptors), but decoding any private descriptors
+    /// according to the `private_data_specifier` in effect.  See the
+    /// [`private_data`](../private_data/index.html) module.
+    pub fn descriptors_with_private<Desc, Priv>(&self) -> PrivateDescriptorIter<'buf, Desc, Priv>
+    where
+        Desc: descriptor::Descriptor<'buf>,
+        Priv: PrivateDescriptor<'buf>,
+    {
+        PrivateDescriptorIter::new(self.descriptors_data())
     }
 }
 struct DescriptorsDebug<'buf, Desc: descriptor::Descriptor<'buf>>(
@@ -0,0 +1,217 @@
+//! _Extension Descriptor_ (tag 0x7F)
+//!
+//! Descriptors defined after the 8-bit tag space was exhausted are carried within an
+//! [`ExtensionDescriptor`], and identified by a second-level `descriptor_tag_extension`.  Use
+//! [`ExtensionDescriptor::descriptor()`] to decode the contained descriptor as one of the
+//! [`ExtensionDescriptors`] variants.
+use crate::audio::{
+    Ac4Descriptor, AudioPreselectionDescriptor, DtsHdDescriptor, DtsUhdDescriptor,
+    SupplementaryAudioDescriptor,
+};
+use crate::image_icon::ImageIconDescriptor;
+use crate::network_change::{MessageDescriptor, NetworkChangeNotifyDescriptor};
+use crate::service_move::ServiceRelocatedDescriptor;
+use crate::subtitling::TtmlSubtitlingDescriptor;
+use crate::target_region::{TargetRegionDescriptor, TargetRegionNameDescriptor};
+use crate::uri_linkage::UriLinkageDescriptor;
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Mirrors `mpeg2ts_reader::descriptor_enum!`, but dispatching on the `descriptor_tag_extension`
+/// of an extension descriptor.  Each variant's type must provide a
+/// `new(tag_extension, data)` constructor.
+///
+/// Unlike `descriptor_enum!`, the listed patterns must cover every `descriptor_tag_extension`
+/// value.
+macro_rules! extension_descriptor_enum {
+    (
+        $(#[$outer:meta])*
+        $name:ident {
+            $(
+                $(#[$inner:ident $($args:tt)*])*
+                $case_name:ident $($tags:pat_param)|* => $t:ident
+            ),*,
+        }
+    ) => {
+        $(#[$outer])*
+        pub enum $name<'buf> {
+            $(
+                $(#[$inner $($args)*])*
+                $case_name($t<'buf>),
+            )*
+        }
+        impl<'buf> $name<'buf> {
+            /// Decode the body of an extension descriptor having the given
+            /// `descriptor_tag_extension`.
+            pub fn from_tag_extension(
+                tag_extension: u8,
+                data: &'buf [u8],
+            ) -> Result<Self, descriptor::DescriptorError> {
+                match tag_extension {
+                    $( $( $tags )|* => Ok($name::$case_name($t::new(tag_extension, data)?)), )*
+                }
+            }
+        }
+    }
+}
+
+extension_descriptor_enum! {
+    /// All extension descriptors supported by this crate, as identified by their
+    /// `descriptor_tag_extension`.
+    ///
+    /// Future releases of this crate should replace most `UnknownExtensionDescriptor` with
+    /// descriptor-specific implementations.
+    #[derive(Debug)]
+    ExtensionDescriptors {
+        ImageIcon ImageIconDescriptor::TAG_EXTENSION => ImageIconDescriptor,
+        CpcmDeliverySignalling 0x01 => UnknownExtensionDescriptor,
+        CP 0x02 => UnknownExtensionDescriptor,
+        CPIdentifier 0x03 => UnknownExtensionDescriptor,
+        T2DeliverySystem 0x04 => UnknownExtensionDescriptor,
+        SHDeliverySystem 0x05 => UnknownExtensionDescriptor,
+        SupplementaryAudio SupplementaryAudioDescriptor::TAG_EXTENSION => SupplementaryAudioDescriptor,
+        NetworkChangeNotify NetworkChangeNotifyDescriptor::TAG_EXTENSION => NetworkChangeNotifyDescriptor,
+        Message MessageDescriptor::TAG_EXTENSION => MessageDescriptor,
+        TargetRegion TargetRegionDescriptor::TAG_EXTENSION => TargetRegionDescriptor,
+        TargetRegionName TargetRegionNameDescriptor::TAG_EXTENSION => TargetRegionNameDescriptor,
+        ServiceRelocated ServiceRelocatedDescriptor::TAG_EXTENSION => ServiceRelocatedDescriptor,
+        XAITPid 0x0C => UnknownExtensionDescriptor,
+        C2DeliverySystem 0x0D => UnknownExtensionDescriptor,
+        DtsHd DtsHdDescriptor::TAG_EXTENSION => DtsHdDescriptor,
+        DtsNeural 0x0F => UnknownExtensionDescriptor,
+        VideoDepthRange 0x10 => UnknownExtensionDescriptor,
+        T2MI 0x11 => UnknownExtensionDescriptor,
+        URILinkage UriLinkageDescriptor::TAG_EXTENSION => UriLinkageDescriptor,
+        CIAncillaryData 0x14 => UnknownExtensionDescriptor,
+        Ac4 Ac4Descriptor::TAG_EXTENSION => Ac4Descriptor,
+        C2BundleDeliverySystem 0x16 => UnknownExtensionDescriptor,
+        S2XSatelliteDeliverySystem 0x17 => UnknownExtensionDescriptor,
+        ProtectionMessage 0x18 => UnknownExtensionDescriptor,
+        AudioPreselection AudioPreselectionDescriptor::TAG_EXTENSION => AudioPreselectionDescriptor,
+        TTMLSubtitling TtmlSubtitlingDescriptor::TAG_EXTENSION => TtmlSubtitlingDescriptor,
+        DtsUhd DtsUhdDescriptor::TAG_EXTENSION => DtsUhdDescriptor,
+        ServiceProminence 0x22 => UnknownExtensionDescriptor,
+        VvcSubpictures 0x23 => UnknownExtensionDescriptor,
+        S2Xv2SatelliteDeliverySystem 0x24 => UnknownExtensionDescriptor,
+        Reserved 0x12|0x1A..=0x1F|0x25..=0x7F => UnknownExtensionDescriptor,
+        UserDefined 0x80..=0xFF => UnknownExtensionDescriptor,
+    }
+}
+
+/// The body of an extension descriptor which this crate does not (yet) decode
+pub struct UnknownExtensionDescriptor<'buf> {
+    pub tag_extension: u8,
+    pub payload: &'buf [u8],
+}
+impl<'buf> UnknownExtensionDescriptor<'buf> {
+    pub fn new(
+        tag_extension: u8,
+        payload: &'buf [u8],
+    ) -> Result<UnknownExtensionDescriptor<'buf>, descriptor::DescriptorError> {
+        Ok(UnknownExtensionDescriptor {
+            tag_extension,
+            payload,
+        })
+    }
+}
+impl<'buf> fmt::Debug for UnknownExtensionDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("UnknownExtensionDescriptor")
+            .field("tag_extension", &self.tag_extension)
+            .field("len", &self.payload.len())
+            .finish()
+    }
+}
+
+/// Carries one of the descriptors defined by _ETSI EN 300 468_ after the 8-bit tag space was
+/// exhausted, as identified by a second-level `descriptor_tag_extension` value, as defined in
+/// _ETSI EN 300 468_ section 6.2.16.
+pub struct ExtensionDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ExtensionDescriptor<'buf> {
+    pub const TAG: u8 = 0x7F;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<ExtensionDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        Ok(ExtensionDescriptor { data })
+    }
+
+    pub fn descriptor_tag_extension(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// The body of the extension descriptor, following `descriptor_tag_extension`
+    pub fn selector_bytes(&self) -> &'buf [u8] {
+        &self.data[1..]
+    }
+
+    /// Decode the contained descriptor according to its `descriptor_tag_extension`
+    pub fn descriptor(&self) -> Result<ExtensionDescriptors<'buf>, descriptor::DescriptorError> {
+        ExtensionDescriptors::from_tag_extension(
+            self.descriptor_tag_extension(),
+            self.selector_bytes(),
+        )
+    }
+}
+impl<'buf> fmt::Debug for ExtensionDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.descriptor() {
+            Ok(desc) => desc.fmt(f),
+            Err(e) => f
+                .debug_struct("ExtensionDescriptor")
+                .field("descriptor_tag_extension", &self.descriptor_tag_extension())
+                .field("error", &e)
+                .finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::En300_468Descriptors;
+
+    #[test]
+    fn extension_descriptors() {
+        #[rustfmt::skip]
+        let data = [
+            // service_relocated_descriptor
+            0x7F, 0x07, 0x0B, 0x00, 0x01, 0x00, 0x02, 0x10, 0x01,
+            // user defined
+            0x7F, 0x03, 0x80, 0xAA, 0xBB,
+            // truncated service_relocated_descriptor
+            0x7F, 0x03, 0x0B, 0x00, 0x01,
+        ];
+        let exts: Vec<_> = descriptor::DescriptorIter::new(&data[..])
+            .map(|d| match d {
+                Ok(En300_468Descriptors::Extension(ext)) => ext,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        match exts[0].descriptor() {
+            Ok(ExtensionDescriptors::ServiceRelocated(d)) => {
+                assert_eq!(0x1001, d.old_service().service_id)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match exts[1].descriptor() {
+            Ok(ExtensionDescriptors::UserDefined(d)) => {
+                assert_eq!(0x80, d.tag_extension);
+                assert_eq!(&[0xAA, 0xBB], d.payload);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(exts[2].descriptor().is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! _FTA Content Management Descriptor_ (tag 0x7E)
+//!
+//! The descriptor may be placed in the EIT, SDT, BAT or NIT, and the one at the most specific
+//! level applies.  See [`content_management()`].
+use crate::En300_468Descriptors;
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Whether content may be accessed remotely over the Internet (for example, when streamed from a
+/// home network PVR)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RemoteAccess {
+    /// Remote access is permitted
+    Enabled,
+    /// Remote access is permitted only within a managed domain
+    ManagedDomain,
+    /// Remote access is permitted only within a managed domain, and only after a certain short
+    /// period of time (e.g. 24 hours)
+    ManagedDomainAfterDelay,
+    /// Remote access is not permitted
+    NotAllowed,
+}
+impl RemoteAccess {
+    pub fn from_id(id: u8) -> RemoteAccess {
+        match id {
+            0 => RemoteAccess::Enabled,
+            1 => RemoteAccess::ManagedDomain,
+            2 => RemoteAccess::ManagedDomainAfterDelay,
+            3 => RemoteAccess::NotAllowed,
+            _ => panic!(
+                "Invalid control_remote_access_over_internet value {} (must be less than 4)",
+                id
+            ),
+        }
+    }
+}
+
+/// Signals the content management policy for free-to-air content, as defined in
+/// _ETSI EN 300 468_ section 6.2.18.
+pub struct FtaContentManagementDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> FtaContentManagementDescriptor<'buf> {
+    pub const TAG: u8 = 0x7E;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<FtaContentManagementDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        Ok(FtaContentManagementDescriptor { data })
+    }
+
+    pub fn user_defined(&self) -> bool {
+        self.data[0] & 0b1000_0000 != 0
+    }
+
+    /// If `true`, scrambling of the content on recording or redistribution is not permitted
+    pub fn do_not_scramble(&self) -> bool {
+        self.data[0] & 0b0000_1000 != 0
+    }
+
+    pub fn control_remote_access_over_internet(&self) -> RemoteAccess {
+        RemoteAccess::from_id((self.data[0] >> 1) & 0b11)
+    }
+
+    /// If `true`, content revocation must not be applied to the content
+    pub fn do_not_apply_revocation(&self) -> bool {
+        self.data[0] & 0b0000_0001 != 0
+    }
+}
+impl<'buf> fmt::Debug for FtaContentManagementDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("FtaContentManagementDescriptor")
+            .field("user_defined", &self.user_defined())
+            .field("do_not_scramble", &self.do_not_scramble())
+            .field(
+                "control_remote_access_over_internet",
+                &self.control_remote_access_over_internet(),
+            )
+            .field("do_not_apply_revocation", &self.do_not_apply_revocation())
+            .finish()
+    }
+}
+
+/// Finds the content management policy in effect for an event.
+///
+/// Policy set for an event overrides that of its service, which overrides that of the bouquet,
+/// which overrides that of the network, so pass the descriptor loops of the EIT event, SDT
+/// service, BAT and NIT in that order.  Returns `None` if no content management policy is
+/// signalled at any level.
+pub fn content_management<'buf, L>(loops: L) -> Option<FtaContentManagementDescriptor<'buf>>
+where
+    L: IntoIterator,
+    L::Item: IntoIterator<Item = Result<En300_468Descriptors<'buf>, descriptor::DescriptorError>>,
+{
+    crate::find_in_loops(loops, |d| match d {
+        En300_468Descriptors::FTAContentManagement(d) => Some(d),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fta_content_management_descriptor() {
+        let desc = FtaContentManagementDescriptor::new(0x7E, &[0b0111_1101]).unwrap();
+        assert!(!desc.user_defined());
+        assert!(desc.do_not_scramble());
+        assert_eq!(
+            RemoteAccess::ManagedDomainAfterDelay,
+            desc.control_remote_access_over_internet()
+        );
+        assert!(desc.do_not_apply_revocation());
+        assert!(FtaContentManagementDescriptor::new(0x7E, &[]).is_err());
+    }
+
+    #[test]
+    fn inheritance() {
+        let event_loop = [0x4D, 0x00];
+        let service_loop = [0x7E, 0x01, 0b0111_0110];
+        let network_loop = [0x7E, 0x01, 0b0111_1001];
+        let desc = content_management([
+            descriptor::DescriptorIter::new(&event_loop[..]),
+            descriptor::DescriptorIter::new(&service_loop[..]),
+            descriptor::DescriptorIter::new(&network_loop[..]),
+        ])
+        .unwrap();
+        assert!(!desc.do_not_scramble());
+        assert_eq!(
+            RemoteAccess::NotAllowed,
+            desc.control_remote_access_over_internet()
+        );
+
+        let desc = content_management([
+            descriptor::DescriptorIter::new(&event_loop[..]),
+            descriptor::DescriptorIter::new(&network_loop[..]),
+        ])
+        .unwrap();
+        assert!(desc.do_not_scramble());
+        assert_eq!(
+            RemoteAccess::Enabled,
+            desc.control_remote_access_over_internet()
+        );
+
+        assert!(content_management([descriptor::DescriptorIter::new(&event_loop[..])]).is_none());
+    }
+}
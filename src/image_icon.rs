@@ -0,0 +1,439 @@
+//! _Image Icon Descriptor_ (extension tag 0x00)
+//!
+//! An icon (such as a channel logo) may be too large for a single descriptor, in which case its
+//! data is split across several image icon descriptors in the same descriptor loop, numbered by
+//! `descriptor_number`.  Use [`ImageIconAssembler`] to join the fragments into complete
+//! [`ImageIcon`] values.
+use crate::extension::ExtensionDescriptors;
+use crate::En300_468Descriptors;
+use mpeg2ts_reader::descriptor;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// How the icon data is delivered
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IconTransportMode {
+    /// The icon data is carried within the descriptor
+    Local,
+    /// The descriptor carries a URL from which the icon can be retrieved
+    Url,
+    Reserved(u8),
+}
+impl IconTransportMode {
+    pub fn from_id(id: u8) -> IconTransportMode {
+        match id {
+            0 => IconTransportMode::Local,
+            1 => IconTransportMode::Url,
+            2..=3 => IconTransportMode::Reserved(id),
+            _ => panic!(
+                "Invalid icon_transport_mode value {} (must be less than 4)",
+                id
+            ),
+        }
+    }
+}
+
+/// The coordinate system in which an [`IconPosition`] is given
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CoordinateSystem {
+    Res720x576,
+    Res1280x720,
+    Res1920x1080,
+    Reserved(u8),
+    UserPrivate,
+}
+impl CoordinateSystem {
+    pub fn from_id(id: u8) -> CoordinateSystem {
+        match id {
+            0 => CoordinateSystem::Res720x576,
+            1 => CoordinateSystem::Res1280x720,
+            2 => CoordinateSystem::Res1920x1080,
+            3..=6 => CoordinateSystem::Reserved(id),
+            7 => CoordinateSystem::UserPrivate,
+            _ => panic!(
+                "Invalid coordinate_system value {} (must be less than 8)",
+                id
+            ),
+        }
+    }
+}
+
+/// The position at which an icon should be displayed, given as the coordinates of its top-left
+/// corner
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IconPosition {
+    pub coordinate_system: CoordinateSystem,
+    pub horizontal_origin: u16,
+    pub vertical_origin: u16,
+}
+
+/// Offsets of the variable-length fields of an image icon descriptor
+struct Layout {
+    /// Start of `icon_type` (for `descriptor_number` zero only)
+    icon_type: usize,
+    /// Start of the length-prefixed icon data or URL
+    data: usize,
+}
+impl Layout {
+    /// `Err` gives the number of bytes required, if `data` is too short
+    fn new(data: &[u8]) -> Result<Layout, usize> {
+        if data.len() < 2 {
+            return Err(2);
+        }
+        let mut layout = if data[0] >> 4 == 0 {
+            let flags = *data.get(2).ok_or(3usize)?;
+            let icon_type = if flags & 0b0010_0000 != 0 { 6 } else { 3 };
+            let icon_type_length = *data.get(icon_type).ok_or(icon_type + 1)?;
+            Layout {
+                icon_type: icon_type + 1,
+                data: icon_type + 1 + usize::from(icon_type_length),
+            }
+        } else {
+            Layout {
+                icon_type: 2,
+                data: 2,
+            }
+        };
+        if data[0] >> 4 == 0 && (data[2] >> 6) > 1 {
+            // reserved transport modes have no data field
+            layout.data = data.len();
+            return if layout.icon_type > data.len() {
+                Err(layout.icon_type)
+            } else {
+                Ok(layout)
+            };
+        }
+        let len = usize::from(*data.get(layout.data).ok_or(layout.data + 1)?);
+        if layout.data + 1 + len > data.len() {
+            Err(layout.data + 1 + len)
+        } else {
+            Ok(layout)
+        }
+    }
+}
+
+/// One fragment of an icon, as defined in _ETSI EN 300 468_ section 6.4.7.
+///
+/// The icon's type, position and transport mode are given only by the fragment with
+/// `descriptor_number` zero.
+///
+/// This is carried within an
+/// [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct ImageIconDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ImageIconDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x00;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<ImageIconDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        if let Err(expected) = Layout::new(data) {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected,
+            });
+        }
+        Ok(ImageIconDescriptor { data })
+    }
+
+    fn layout(&self) -> Layout {
+        // checked by ImageIconDescriptor::new()
+        Layout::new(self.data).unwrap()
+    }
+
+    pub fn descriptor_number(&self) -> u8 {
+        self.data[0] >> 4
+    }
+
+    pub fn last_descriptor_number(&self) -> u8 {
+        self.data[0] & 0b1111
+    }
+
+    pub fn icon_id(&self) -> u8 {
+        self.data[1] & 0b111
+    }
+
+    fn is_first(&self) -> bool {
+        self.descriptor_number() == 0
+    }
+
+    pub fn icon_transport_mode(&self) -> Option<IconTransportMode> {
+        if self.is_first() {
+            Some(IconTransportMode::from_id(self.data[2] >> 6))
+        } else {
+            None
+        }
+    }
+
+    pub fn position(&self) -> Option<IconPosition> {
+        if !self.is_first() || self.data[2] & 0b0010_0000 == 0 {
+            return None;
+        }
+        Some(IconPosition {
+            coordinate_system: CoordinateSystem::from_id((self.data[2] >> 2) & 0b111),
+            horizontal_origin: u16::from(self.data[3]) << 4 | u16::from(self.data[4] >> 4),
+            vertical_origin: u16::from(self.data[4] & 0b1111) << 8 | u16::from(self.data[5]),
+        })
+    }
+
+    /// The MIME type of the icon, e.g. `image/png`
+    pub fn icon_type(&self) -> Option<&'buf [u8]> {
+        if !self.is_first() {
+            return None;
+        }
+        let layout = self.layout();
+        Some(&self.data[layout.icon_type..layout.data])
+    }
+
+    /// The icon data (or, for the `Url` transport mode, URL characters) carried by this fragment
+    pub fn icon_data(&self) -> &'buf [u8] {
+        let pos = self.layout().data;
+        match self.data.get(pos) {
+            Some(len) => &self.data[pos + 1..pos + 1 + usize::from(*len)],
+            None => &[],
+        }
+    }
+}
+impl<'buf> fmt::Debug for ImageIconDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut s = f.debug_struct("ImageIconDescriptor");
+        s.field("descriptor_number", &self.descriptor_number())
+            .field("last_descriptor_number", &self.last_descriptor_number())
+            .field("icon_id", &self.icon_id());
+        if self.is_first() {
+            s.field("icon_transport_mode", &self.icon_transport_mode())
+                .field("position", &self.position())
+                .field("icon_type", &self.icon_type().map(String::from_utf8_lossy));
+        }
+        s.field("len", &self.icon_data().len()).finish()
+    }
+}
+
+/// Where the image of a complete [`ImageIcon`] is to be found
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IconContent {
+    /// The image data
+    Data(Vec<u8>),
+    /// A URL from which the image can be retrieved
+    Url(String),
+    Reserved(u8),
+}
+
+/// An icon assembled from all its [`ImageIconDescriptor`] fragments
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ImageIcon {
+    pub icon_id: u8,
+    /// The MIME type of the image, e.g. `image/png`
+    pub icon_type: String,
+    pub position: Option<IconPosition>,
+    pub content: IconContent,
+}
+impl ImageIcon {
+    /// A file extension suitable for the image's MIME type, if it is a commonly used one
+    pub fn file_extension(&self) -> Option<&'static str> {
+        match self.icon_type.to_ascii_lowercase().as_str() {
+            "image/png" => Some("png"),
+            "image/jpeg" | "image/jpg" => Some("jpg"),
+            "image/gif" => Some("gif"),
+            "image/svg+xml" => Some("svg"),
+            "image/webp" => Some("webp"),
+            _ => None,
+        }
+    }
+
+    /// Write the image data to the given file.
+    ///
+    /// Fails with `io::ErrorKind::InvalidInput` if the image is not carried inline (i.e. if
+    /// `content` is not `IconContent::Data`).
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        match self.content {
+            IconContent::Data(ref data) => std::fs::write(path, data),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "icon data is not carried inline",
+            )),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Fragments {
+    last_descriptor_number: u8,
+    header: Option<(IconTransportMode, Option<IconPosition>, String)>,
+    parts: Vec<Option<Vec<u8>>>,
+}
+impl Fragments {
+    fn icon(&self, icon_id: u8) -> Option<ImageIcon> {
+        let (mode, position, icon_type) = self.header.as_ref()?;
+        let mut data = vec![];
+        for part in &self.parts {
+            data.extend_from_slice(part.as_ref()?);
+        }
+        let content = match mode {
+            IconTransportMode::Local => IconContent::Data(data),
+            IconTransportMode::Url => IconContent::Url(String::from_utf8_lossy(&data).into_owned()),
+            IconTransportMode::Reserved(v) => IconContent::Reserved(*v),
+        };
+        Some(ImageIcon {
+            icon_id,
+            icon_type: icon_type.clone(),
+            position: *position,
+            content,
+        })
+    }
+}
+
+/// Joins the fragments of icons split across several [`ImageIconDescriptor`] instances.
+///
+/// Fragments should be given from a single descriptor loop (i.e. for one service or event),
+/// since `icon_id` values are only unique within a loop.
+#[derive(Default)]
+pub struct ImageIconAssembler {
+    icons: HashMap<u8, Fragments>,
+}
+impl ImageIconAssembler {
+    pub fn new() -> ImageIconAssembler {
+        ImageIconAssembler::default()
+    }
+
+    /// Record the given fragment.  A fragment whose `last_descriptor_number` differs from that of
+    /// fragments already received for the same `icon_id` discards them.
+    pub fn add(&mut self, desc: &ImageIconDescriptor<'_>) {
+        let last = desc.last_descriptor_number();
+        let frags = self.icons.entry(desc.icon_id()).or_default();
+        if frags.parts.len() != usize::from(last) + 1 || frags.last_descriptor_number != last {
+            *frags = Fragments {
+                last_descriptor_number: last,
+                header: None,
+                parts: vec![None; usize::from(last) + 1],
+            };
+        }
+        let num = usize::from(desc.descriptor_number());
+        if num >= frags.parts.len() {
+            return;
+        }
+        if let (Some(mode), Some(icon_type)) = (desc.icon_transport_mode(), desc.icon_type()) {
+            frags.header = Some((
+                mode,
+                desc.position(),
+                String::from_utf8_lossy(icon_type).into_owned(),
+            ));
+        }
+        frags.parts[num] = Some(desc.icon_data().to_vec());
+    }
+
+    /// Record any image icon fragments in the given descriptor loop
+    pub fn add_descriptors<'buf>(
+        &mut self,
+        descs: impl IntoIterator<Item = Result<En300_468Descriptors<'buf>, descriptor::DescriptorError>>,
+    ) {
+        for desc in descs.into_iter().flatten() {
+            if let En300_468Descriptors::Extension(ext) = desc {
+                if let Ok(ExtensionDescriptors::ImageIcon(d)) = ext.descriptor() {
+                    self.add(&d);
+                }
+            }
+        }
+    }
+
+    /// The icon with the given id, if all its fragments have been received
+    pub fn icon(&self, icon_id: u8) -> Option<ImageIcon> {
+        self.icons.get(&icon_id)?.icon(icon_id)
+    }
+
+    /// All icons for which every fragment has been received, in order of `icon_id`
+    pub fn icons(&self) -> Vec<ImageIcon> {
+        let mut ids: Vec<_> = self.icons.keys().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter().filter_map(|id| self.icon(id)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[rustfmt::skip]
+    const LOOP: [u8; 40] = [
+        // icon 1, fragment 0 of 0..=1, positioned at (100, 50) in 1280x720
+        0x7F, 0x15, 0x00,
+        0x01, 0xF9, 0b0010_0100, 0x06, 0x40, 0x32,
+        0x09, b'i', b'm', b'a', b'g', b'e', b'/', b'p', b'n', b'g',
+        0x03, 0x89, b'P', b'N',
+        // icon 2, given by URL
+        0x7F, 0x08, 0x00,
+        0x00, 0xFA, 0b0100_0000, 0x00, 0x02, b'/', b'x',
+        // icon 1, fragment 1
+        0x7F, 0x05, 0x00,
+        0x11, 0xF9, 0x01, b'G',
+    ];
+
+    #[test]
+    fn image_icon_descriptor() {
+        let data = &LOOP[3..23];
+        let desc = ImageIconDescriptor::new(0x00, data).unwrap();
+        assert_eq!(0, desc.descriptor_number());
+        assert_eq!(1, desc.last_descriptor_number());
+        assert_eq!(1, desc.icon_id());
+        assert_eq!(Some(IconTransportMode::Local), desc.icon_transport_mode());
+        assert_eq!(
+            Some(IconPosition {
+                coordinate_system: CoordinateSystem::Res1280x720,
+                horizontal_origin: 100,
+                vertical_origin: 50,
+            }),
+            desc.position()
+        );
+        assert_eq!(Some(&b"image/png"[..]), desc.icon_type());
+        assert_eq!(&[0x89, b'P', b'N'], desc.icon_data());
+        let expected = |len| match ImageIconDescriptor::new(0x00, &data[..len]) {
+            Err(descriptor::DescriptorError::NotEnoughData { expected, .. }) => expected,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(2, expected(1));
+        assert_eq!(7, expected(5));
+        assert_eq!(17, expected(12));
+        assert_eq!(20, expected(19));
+    }
+
+    #[test]
+    fn assemble() {
+        let mut assembler = ImageIconAssembler::new();
+        assembler.add_descriptors(descriptor::DescriptorIter::new(&LOOP[..33]));
+        assert_eq!(None, assembler.icon(1));
+
+        let mut assembler = ImageIconAssembler::new();
+        assembler.add_descriptors(descriptor::DescriptorIter::new(&LOOP[..]));
+        let icons = assembler.icons();
+        assert_eq!(2, icons.len());
+        assert_eq!(
+            IconContent::Data(vec![0x89, b'P', b'N', b'G']),
+            icons[0].content
+        );
+        assert_eq!(Some("png"), icons[0].file_extension());
+        assert_eq!(IconContent::Url("/x".to_string()), icons[1].content);
+        assert_eq!("", icons[1].icon_type);
+        assert!(icons[1].save("unused").is_err());
+    }
+
+    #[test]
+    fn save() {
+        let icon = ImageIcon {
+            icon_id: 1,
+            icon_type: "image/png".to_string(),
+            position: None,
+            content: IconContent::Data(vec![0x89, b'P', b'N', b'G']),
+        };
+        let path = std::env::temp_dir().join(format!("image_icon_{}.png", std::process::id()));
+        icon.save(&path).unwrap();
+        let saved = std::fs::read(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(vec![0x89, b'P', b'N', b'G'], saved.unwrap());
+    }
+}
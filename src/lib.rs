@@ -3,17 +3,67 @@
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms, future_incompatible)]
 
+pub mod accessibility;
+pub mod announcement;
+pub mod audio;
+pub mod ca;
+pub mod component;
+pub mod country_availability;
+pub mod crid;
+pub mod data_broadcast;
 pub mod eit;
+pub mod extension;
+pub mod fta_content_management;
 mod huffman;
+pub mod image_icon;
+pub mod local_time_offset;
+pub mod mosaic;
+pub mod multilingual;
+pub mod network_change;
+pub mod nvod;
+pub mod pdc;
+pub mod private_data;
 pub mod sdt;
+pub mod service_availability;
+pub mod service_move;
 pub mod short_event;
+pub mod subtitling;
+pub mod target_region;
 pub mod tdt;
+pub mod teletext;
 pub mod time;
+pub mod uri_linkage;
 
 use mpeg2ts_reader::descriptor::UnknownDescriptor;
 
+use crate::announcement::AnnouncementSupportDescriptor;
+use crate::audio::{
+    AacDescriptor, Ac3Descriptor, DtsDescriptor, EnhancedAc3Descriptor, Iso639LanguageDescriptor,
+};
+use crate::ca::{CaDescriptor, CaIdentifierDescriptor, ScramblingDescriptor};
+use crate::component::{ComponentDescriptor, StreamIdentifierDescriptor};
+use crate::country_availability::CountryAvailabilityDescriptor;
+use crate::crid::{ContentIdentifierDescriptor, DefaultAuthorityDescriptor};
+use crate::data_broadcast::{DataBroadcastDescriptor, DataBroadcastIdDescriptor};
+use crate::extension::ExtensionDescriptor;
+use crate::fta_content_management::FtaContentManagementDescriptor;
+use crate::local_time_offset::LocalTimeOffsetDescriptor;
+use crate::mosaic::MosaicDescriptor;
+use crate::multilingual::{
+    MultilingualBouquetNameDescriptor, MultilingualComponentDescriptor,
+    MultilingualNetworkNameDescriptor, MultilingualServiceNameDescriptor,
+};
+use crate::nvod::{
+    NvodReferenceDescriptor, TimeShiftedEventDescriptor, TimeShiftedServiceDescriptor,
+};
+use crate::pdc::PdcDescriptor;
+use crate::private_data::PrivateDataSpecifierDescriptor;
 use crate::sdt::ServiceDescriptor;
+use crate::service_availability::ServiceAvailabilityDescriptor;
+use crate::service_move::ServiceMoveDescriptor;
 use crate::short_event::ShortEventDescriptor;
+use crate::subtitling::SubtitlingDescriptor;
+use crate::teletext::{TeletextDescriptor, VbiDataDescriptor, VbiTeletextDescriptor};
 use std::borrow::Cow;
 use std::fmt;
 
@@ -32,8 +82,8 @@ mpeg2ts_reader::descriptor_enum! {
         DataStreamAlignment 6 => UnknownDescriptor,
         TargetBackgroundGrid 7 => UnknownDescriptor,
         VideoWindow 8 => UnknownDescriptor,
-        CA 9 => UnknownDescriptor,
-        ISO639Language 10 => UnknownDescriptor,
+        CA CaDescriptor::TAG => CaDescriptor,
+        ISO639Language Iso639LanguageDescriptor::TAG => Iso639LanguageDescriptor,
         SystemClock 11 => UnknownDescriptor,
         MultiplexBufferUtilization 12 => UnknownDescriptor,
         Copyright 13 => UnknownDescriptor,
@@ -53,7 +103,9 @@ mpeg2ts_reader::descriptor_enum! {
         MuxCode 33 => UnknownDescriptor,
         FmxBufferSize 34 => UnknownDescriptor,
         MultiplexBuffer 35 => UnknownDescriptor,
-        UserPrivate 69..=70|103..=254 => UnknownDescriptor,
+        /// Tags 0x80 to 0xFE can only be decoded given the `private_data_specifier` in effect; see
+        /// [`private_data::PrivateDescriptorIter`](private_data/struct.PrivateDescriptorIter.html).
+        UserPrivate 0x80..=0xFE => UnknownDescriptor,
 
         // EN 300 480 specofic descriptors,
         NetworkName 0x40 => UnknownDescriptor,
@@ -61,40 +113,67 @@ mpeg2ts_reader::descriptor_enum! {
         Stuffing 0x42 => UnknownDescriptor,
         SatelliteDeliverySystem 0x43 => UnknownDescriptor,
         CableDeliverySystem 0x44 => UnknownDescriptor,
+        VbiData VbiDataDescriptor::TAG => VbiDataDescriptor,
+        VbiTeletext VbiTeletextDescriptor::TAG => VbiTeletextDescriptor,
         BouquetName 0x47 => UnknownDescriptor,
 
         Service ServiceDescriptor::TAG => ServiceDescriptor,
 
-        CountryAvailability 0x49 => UnknownDescriptor,
+        CountryAvailability CountryAvailabilityDescriptor::TAG => CountryAvailabilityDescriptor,
         Linkage 0x4A => UnknownDescriptor,
-        NvodReference 0x4B => UnknownDescriptor,
-        TimeShiftedService 0x4C => UnknownDescriptor,
+        NvodReference NvodReferenceDescriptor::TAG => NvodReferenceDescriptor,
+        TimeShiftedService TimeShiftedServiceDescriptor::TAG => TimeShiftedServiceDescriptor,
         ShortEvent ShortEventDescriptor::TAG => ShortEventDescriptor,
         ExtendedEvent 0x4E => UnknownDescriptor,
-        TimeShiftedEvent 0x4F => UnknownDescriptor,
-        Component 0x50 => UnknownDescriptor,
-        Mosaic 0x51 => UnknownDescriptor,
-        StreamIdentifier 0x52 => UnknownDescriptor,
-        CaIdentifier 0x53 => UnknownDescriptor,
+        TimeShiftedEvent TimeShiftedEventDescriptor::TAG => TimeShiftedEventDescriptor,
+        Component ComponentDescriptor::TAG => ComponentDescriptor,
+        Mosaic MosaicDescriptor::TAG => MosaicDescriptor,
+        StreamIdentifier StreamIdentifierDescriptor::TAG => StreamIdentifierDescriptor,
+        CaIdentifier CaIdentifierDescriptor::TAG => CaIdentifierDescriptor,
         Content 0x54 => UnknownDescriptor,
         ParentalRating 0x55 => UnknownDescriptor,
-        Teletext 0x56 => UnknownDescriptor,
+        Teletext TeletextDescriptor::TAG => TeletextDescriptor,
         Telephone 0x57 => UnknownDescriptor,
-        LocalTimeOffset 0x58 => UnknownDescriptor,
-        Subtitling 0x59 => UnknownDescriptor,
+        LocalTimeOffset LocalTimeOffsetDescriptor::TAG => LocalTimeOffsetDescriptor,
+        Subtitling SubtitlingDescriptor::TAG => SubtitlingDescriptor,
         TerrestrialDeliverySystem 0x5A => UnknownDescriptor,
-        MultilingualNetworkName 0x5B => UnknownDescriptor,
-        MultilingualBouquetName 0x5C => UnknownDescriptor,
-        MultilingualServiceName 0x5D => UnknownDescriptor,
-        MultilingualComponent 0x5E => UnknownDescriptor,
-        PrivateDataSpecifier 0x5F => UnknownDescriptor,
-        ServiceMove 0x60 => UnknownDescriptor,
+        MultilingualNetworkName MultilingualNetworkNameDescriptor::TAG => MultilingualNetworkNameDescriptor,
+        MultilingualBouquetName MultilingualBouquetNameDescriptor::TAG => MultilingualBouquetNameDescriptor,
+        MultilingualServiceName MultilingualServiceNameDescriptor::TAG => MultilingualServiceNameDescriptor,
+        MultilingualComponent MultilingualComponentDescriptor::TAG => MultilingualComponentDescriptor,
+        PrivateDataSpecifier PrivateDataSpecifierDescriptor::TAG => PrivateDataSpecifierDescriptor,
+        ServiceMove ServiceMoveDescriptor::TAG => ServiceMoveDescriptor,
         ShortSmoothingBuffer 0x61 => UnknownDescriptor,
         FrequencyList 0x62 => UnknownDescriptor,
         PartialTransportStream 0x63 => UnknownDescriptor,
-        DataBroadcast 0x64 => UnknownDescriptor,
-        CaSystem 0x65 => UnknownDescriptor,
-        DataBroadcastId 0x66 => UnknownDescriptor,
+        DataBroadcast DataBroadcastDescriptor::TAG => DataBroadcastDescriptor,
+        Scrambling ScramblingDescriptor::TAG => ScramblingDescriptor,
+        DataBroadcastId DataBroadcastIdDescriptor::TAG => DataBroadcastIdDescriptor,
+        TransportStream 0x67 => UnknownDescriptor,
+        DSNG 0x68 => UnknownDescriptor,
+        PDC PdcDescriptor::TAG => PdcDescriptor,
+        AC3 Ac3Descriptor::TAG => Ac3Descriptor,
+        AncillaryData 0x6B => UnknownDescriptor,
+        CellList 0x6C => UnknownDescriptor,
+        CellFrequencyLink 0x6D => UnknownDescriptor,
+        AnnouncementSupport AnnouncementSupportDescriptor::TAG => AnnouncementSupportDescriptor,
+        ApplicationSignalling 0x6F => UnknownDescriptor,
+        AdaptationFieldData 0x70 => UnknownDescriptor,
+        ServiceIdentifier 0x71 => UnknownDescriptor,
+        ServiceAvailability ServiceAvailabilityDescriptor::TAG => ServiceAvailabilityDescriptor,
+        DefaultAuthority DefaultAuthorityDescriptor::TAG => DefaultAuthorityDescriptor,
+        RelatedContent 0x74 => UnknownDescriptor,
+        TVAId 0x75 => UnknownDescriptor,
+        ContentIdentifier ContentIdentifierDescriptor::TAG => ContentIdentifierDescriptor,
+        TimeSliceFecIdentifier 0x77 => UnknownDescriptor,
+        ECMRepetitionRate 0x78 => UnknownDescriptor,
+        S2SatelliteDeliverySystem 0x79 => UnknownDescriptor,
+        EnhancedAC3 EnhancedAc3Descriptor::TAG => EnhancedAc3Descriptor,
+        DTS DtsDescriptor::TAG => DtsDescriptor,
+        AAC AacDescriptor::TAG => AacDescriptor,
+        XAITLocation 0x7D => UnknownDescriptor,
+        FTAContentManagement FtaContentManagementDescriptor::TAG => FtaContentManagementDescriptor,
+        Extension ExtensionDescriptor::TAG => ExtensionDescriptor,
         Forbidden 0xFF => UnknownDescriptor,
     }
 }
@@ -173,6 +252,61 @@ impl<T> ActualOther<T> {
     }
 }
 
+/// Identifies a service anywhere in the DVB world by the combination of its
+/// _original_network_id_, _transport_stream_id_ and _service_id_.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ServiceTriplet {
+    pub original_network_id: u16,
+    pub transport_stream_id: u16,
+    pub service_id: u16,
+}
+impl ServiceTriplet {
+    /// Reads the three 16-bit ids, in the order given above, from the first six bytes of `data`
+    pub(crate) fn from_bytes(data: &[u8]) -> ServiceTriplet {
+        ServiceTriplet {
+            original_network_id: u16::from(data[0]) << 8 | u16::from(data[1]),
+            transport_stream_id: u16::from(data[2]) << 8 | u16::from(data[3]),
+            service_id: u16::from(data[4]) << 8 | u16::from(data[5]),
+        }
+    }
+}
+
+/// Searches descriptor loops given in order of precedence, such as those of an event, its
+/// service, and so on out to the network, returning the first value produced by `f`.  This is
+/// how descriptors signalled at an inner level override those of the levels enclosing it.
+pub(crate) fn find_in_loops<'buf, L, T>(
+    loops: L,
+    mut f: impl FnMut(En300_468Descriptors<'buf>) -> Option<T>,
+) -> Option<T>
+where
+    L: IntoIterator,
+    L::Item: IntoIterator<
+        Item = Result<En300_468Descriptors<'buf>, mpeg2ts_reader::descriptor::DescriptorError>,
+    >,
+{
+    loops
+        .into_iter()
+        .find_map(|descs| descs.into_iter().flatten().find_map(&mut f))
+}
+
+/// Evaluates the inclusion and exclusion lists of availability descriptors, given for each
+/// descriptor its availability flag (`true` for an inclusion list) and whether it lists the item
+/// in question.  An item listed by any exclusion list is unavailable; otherwise it must be listed
+/// by one of the inclusion lists, if there are any.
+pub(crate) fn is_included(lists: impl IntoIterator<Item = (bool, bool)>) -> bool {
+    let mut any_inclusion_list = false;
+    let mut included = false;
+    for (availability_flag, listed) in lists {
+        if availability_flag {
+            any_inclusion_list = true;
+            included |= listed;
+        } else if listed {
+            return false;
+        }
+    }
+    included || !any_inclusion_list
+}
+
 /// A problem encountered by [`Text::to_string()`](struct.Text.html#method.to_string).
 #[derive(Debug)]
 pub enum TextError {
@@ -0,0 +1,225 @@
+//! _Local Time Offset Descriptor_ (tag 0x58)
+use crate::time::{MjdTimestamp, MjdTimestampError};
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Decode a 16-bit BCD `HHMM` value into minutes.
+fn bcd_offset_minutes(hi: u8, lo: u8) -> i32 {
+    let hours = i32::from(hi >> 4) * 10 + i32::from(hi & 0x0f);
+    let minutes = i32::from(lo >> 4) * 10 + i32::from(lo & 0x0f);
+    hours * 60 + minutes
+}
+
+/// A single 13-byte entry from a [`LocalTimeOffsetDescriptor`], giving the offset from UTC for
+/// one country (or region of a country), together with the offset that will apply after the
+/// next change (e.g. to or from daylight saving time).
+pub struct LocalTimeOffsetEntry<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> LocalTimeOffsetEntry<'buf> {
+    const SIZE: usize = 13;
+
+    /// Three-character ISO 3166 country code
+    pub fn country_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 3166 country code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn country_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.country_code()).ok()
+    }
+
+    /// Identifies a time zone within the country, or `0` if the country has a single time zone.
+    pub fn country_region_id(&self) -> u8 {
+        self.data[3] >> 2
+    }
+
+    /// `true` if the offsets are negative (i.e. local time is behind UTC).
+    pub fn local_time_offset_polarity(&self) -> bool {
+        self.data[3] & 0b1 != 0
+    }
+
+    fn apply_polarity(&self, minutes: i32) -> i32 {
+        if self.local_time_offset_polarity() {
+            -minutes
+        } else {
+            minutes
+        }
+    }
+
+    /// The current offset of local time from UTC, in minutes.
+    pub fn local_time_offset(&self) -> i32 {
+        self.apply_polarity(bcd_offset_minutes(self.data[4], self.data[5]))
+    }
+
+    /// The UTC time at which the offset will change from `local_time_offset` to
+    /// `next_time_offset`.
+    pub fn time_of_change(&self) -> Result<MjdTimestamp<'buf>, MjdTimestampError> {
+        MjdTimestamp::new(&self.data[6..11])
+    }
+
+    /// The offset of local time from UTC, in minutes, that applies from `time_of_change`.
+    pub fn next_time_offset(&self) -> i32 {
+        self.apply_polarity(bcd_offset_minutes(self.data[11], self.data[12]))
+    }
+
+    /// The offset of local time from UTC, in minutes, at the given UTC instant (in seconds since
+    /// the Unix epoch, as produced by
+    /// [`MjdTimestamp::to_unix_timestamp()`](../time/struct.MjdTimestamp.html#method.to_unix_timestamp)).
+    ///
+    /// If `time_of_change` is invalid, the current `local_time_offset` is assumed.
+    pub fn offset_at(&self, unix_timestamp: i64) -> i32 {
+        match self.time_of_change() {
+            Ok(change) if unix_timestamp >= change.to_unix_timestamp() => self.next_time_offset(),
+            _ => self.local_time_offset(),
+        }
+    }
+}
+impl<'buf> fmt::Debug for LocalTimeOffsetEntry<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("LocalTimeOffsetEntry")
+            .field("country_code", &self.country_code_str())
+            .field("country_region_id", &self.country_region_id())
+            .field("local_time_offset", &self.local_time_offset())
+            .field(
+                "time_of_change",
+                &self.time_of_change().as_ref().map_err(|e| format!("{}", e)),
+            )
+            .field("next_time_offset", &self.next_time_offset())
+            .finish()
+    }
+}
+
+/// Gives the offset of local time from UTC for one or more countries or regions, as defined
+/// in _ETSI EN 300 468_ section 6.2.20.  Normally carried in the _Time Offset Table_.
+pub struct LocalTimeOffsetDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> LocalTimeOffsetDescriptor<'buf> {
+    pub const TAG: u8 = 0x58;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<LocalTimeOffsetDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(LocalTimeOffsetDescriptor { data })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = LocalTimeOffsetEntry<'buf>> {
+        self.data
+            .chunks_exact(LocalTimeOffsetEntry::SIZE)
+            .map(|data| LocalTimeOffsetEntry { data })
+    }
+
+    /// The entry for the given country (a three-character ISO 3166 code, compared
+    /// case-insensitively) and `country_region_id`.  If there is no entry for the specific
+    /// region, the entry for the country as a whole (region `0`) is used.
+    pub fn entry(
+        &self,
+        country_code: &str,
+        country_region_id: u8,
+    ) -> Option<LocalTimeOffsetEntry<'buf>> {
+        let mut country_entry = None;
+        for entry in self.entries() {
+            if !entry
+                .country_code()
+                .eq_ignore_ascii_case(country_code.as_bytes())
+            {
+                continue;
+            }
+            if entry.country_region_id() == country_region_id {
+                return Some(entry);
+            }
+            if entry.country_region_id() == 0 {
+                country_entry = Some(entry);
+            }
+        }
+        country_entry
+    }
+
+    /// The offset of local time from UTC, in minutes, for the given country and region at the
+    /// given UTC instant, taking account of any change at `time_of_change`.  See
+    /// [`entry()`](#method.entry) and [`LocalTimeOffsetEntry::offset_at()`].
+    pub fn offset_at(
+        &self,
+        country_code: &str,
+        country_region_id: u8,
+        unix_timestamp: i64,
+    ) -> Option<i32> {
+        self.entry(country_code, country_region_id)
+            .map(|e| e.offset_at(unix_timestamp))
+    }
+}
+impl<'buf> fmt::Debug for LocalTimeOffsetDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("LocalTimeOffsetDescriptor")
+            .field("entries", &self.entries().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[rustfmt::skip]
+    const DATA: [u8; 39] = [
+        // GBR: +00:00, changing to +01:00 at 2024-03-31 01:00:00 UTC
+        b'G', b'B', b'R', 0b0000_0010, 0x00, 0x00, 0xEB, 0xF0, 0x01, 0x00, 0x00, 0x01, 0x00,
+        // ESP region 1: +01:00, changing to +02:00 at 2024-03-31 01:00:00 UTC
+        b'E', b'S', b'P', 0b0000_0110, 0x01, 0x00, 0xEB, 0xF0, 0x01, 0x00, 0x00, 0x02, 0x00,
+        // ESP region 2 (Canary Islands): +00:00, changing to +01:00 at the same time
+        b'E', b'S', b'P', 0b0000_1010, 0x00, 0x00, 0xEB, 0xF0, 0x01, 0x00, 0x00, 0x01, 0x00,
+    ];
+
+    #[test]
+    fn local_time_offset_descriptor() {
+        let desc = LocalTimeOffsetDescriptor::new(0x58, &DATA).unwrap();
+        let entries: Vec<_> = desc.entries().collect();
+        assert_eq!(3, entries.len());
+        assert_eq!(Some("GBR"), entries[0].country_code_str());
+        assert_eq!(0, entries[0].country_region_id());
+        assert!(!entries[0].local_time_offset_polarity());
+        assert_eq!(0, entries[0].local_time_offset());
+        assert_eq!(60, entries[0].next_time_offset());
+        let change = entries[0].time_of_change().unwrap();
+        assert_eq!((2024, 3, 31), change.date());
+        assert_eq!(1, change.hours());
+        assert_eq!(2, entries[2].country_region_id());
+    }
+
+    #[test]
+    fn negative_offset() {
+        #[rustfmt::skip]
+        let data = [
+            b'B', b'R', b'A', 0b0000_0011, 0x03, 0x00, 0xEB, 0xF0, 0x01, 0x00, 0x00, 0x02, 0x30,
+        ];
+        let desc = LocalTimeOffsetDescriptor::new(0x58, &data).unwrap();
+        let entry = desc.entries().next().unwrap();
+        assert_eq!(-180, entry.local_time_offset());
+        assert_eq!(-150, entry.next_time_offset());
+    }
+
+    #[test]
+    fn offset_at() {
+        let desc = LocalTimeOffsetDescriptor::new(0x58, &DATA).unwrap();
+        let change = desc
+            .entries()
+            .next()
+            .unwrap()
+            .time_of_change()
+            .unwrap()
+            .to_unix_timestamp();
+        assert_eq!(Some(0), desc.offset_at("gbr", 0, change - 1));
+        assert_eq!(Some(60), desc.offset_at("GBR", 0, change));
+        // No entry for the specific region, so the country-wide entry applies
+        assert_eq!(Some(60), desc.offset_at("GBR", 3, change + 3600));
+        assert_eq!(Some(60), desc.offset_at("ESP", 1, change - 1));
+        assert_eq!(Some(120), desc.offset_at("ESP", 1, change));
+        assert_eq!(Some(0), desc.offset_at("ESP", 2, change - 1));
+        assert_eq!(None, desc.offset_at("ESP", 3, change));
+        assert_eq!(None, desc.offset_at("FRA", 0, change));
+    }
+}
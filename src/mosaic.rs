@@ -0,0 +1,364 @@
+//! _Mosaic Descriptor_ (tag 0x51)
+//!
+//! A mosaic service presents a grid of _elementary cells_, numbered from zero left-to-right and
+//! top-to-bottom.  Adjacent elementary cells are grouped into _logical cells_, each of which
+//! typically shows a different service and may be linked to it, allowing a viewer to select it.
+use crate::ServiceTriplet;
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// The kind of content presented in a [`LogicalCell`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CellPresentationInfo {
+    Undefined,
+    Video,
+    StillPicture,
+    GraphicsText,
+    Reserved(u8),
+}
+impl CellPresentationInfo {
+    pub fn from_id(id: u8) -> CellPresentationInfo {
+        match id {
+            0 => CellPresentationInfo::Undefined,
+            1 => CellPresentationInfo::Video,
+            2 => CellPresentationInfo::StillPicture,
+            3 => CellPresentationInfo::GraphicsText,
+            4..=7 => CellPresentationInfo::Reserved(id),
+            _ => panic!(
+                "Invalid logical_cell_presentation_info value {} (must be between 0 and 7)",
+                id
+            ),
+        }
+    }
+}
+
+/// What a [`LogicalCell`] is linked to
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CellLinkage {
+    Undefined,
+    Bouquet {
+        bouquet_id: u16,
+    },
+    Service(ServiceTriplet),
+    /// Another mosaic service
+    OtherMosaic(ServiceTriplet),
+    Event {
+        service: ServiceTriplet,
+        event_id: u16,
+    },
+    Reserved(u8),
+}
+impl CellLinkage {
+    /// Size of the data following `cell_linkage_info` for each value
+    fn data_len(cell_linkage_info: u8) -> usize {
+        match cell_linkage_info {
+            0x01 => 2,
+            0x02 | 0x03 => 6,
+            0x04 => 8,
+            _ => 0,
+        }
+    }
+}
+
+/// One logical cell of a [`MosaicDescriptor`], made up of one or more elementary cells
+pub struct LogicalCell<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> LogicalCell<'buf> {
+    pub fn logical_cell_id(&self) -> u8 {
+        self.data[0] >> 2
+    }
+
+    pub fn logical_cell_presentation_info(&self) -> CellPresentationInfo {
+        CellPresentationInfo::from_id(self.data[1] & 0b111)
+    }
+
+    fn elementary_cell_field_length(&self) -> usize {
+        usize::from(self.data[2])
+    }
+
+    /// The ids of the elementary cells making up this logical cell
+    pub fn elementary_cell_ids(&self) -> impl Iterator<Item = u8> + 'buf {
+        self.data[3..3 + self.elementary_cell_field_length()]
+            .iter()
+            .map(|id| id & 0b11_1111)
+    }
+
+    pub fn cell_linkage(&self) -> CellLinkage {
+        let pos = 3 + self.elementary_cell_field_length();
+        let data = &self.data[pos + 1..];
+        match self.data[pos] {
+            0x00 => CellLinkage::Undefined,
+            0x01 => CellLinkage::Bouquet {
+                bouquet_id: u16::from(data[0]) << 8 | u16::from(data[1]),
+            },
+            0x02 => CellLinkage::Service(ServiceTriplet::from_bytes(data)),
+            0x03 => CellLinkage::OtherMosaic(ServiceTriplet::from_bytes(data)),
+            0x04 => CellLinkage::Event {
+                service: ServiceTriplet::from_bytes(data),
+                event_id: u16::from(data[6]) << 8 | u16::from(data[7]),
+            },
+            v => CellLinkage::Reserved(v),
+        }
+    }
+}
+impl<'buf> fmt::Debug for LogicalCell<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("LogicalCell")
+            .field("logical_cell_id", &self.logical_cell_id())
+            .field(
+                "logical_cell_presentation_info",
+                &self.logical_cell_presentation_info(),
+            )
+            .field(
+                "elementary_cell_ids",
+                &self.elementary_cell_ids().collect::<Vec<_>>(),
+            )
+            .field("cell_linkage", &self.cell_linkage())
+            .finish()
+    }
+}
+
+/// Length of the logical cell entry at the start of `data`, or `Err` giving the length the
+/// entry requires if it is incomplete
+fn logical_cell_len(data: &[u8]) -> Result<usize, usize> {
+    let linkage_pos = 3 + usize::from(*data.get(2).ok_or(3usize)?);
+    let linkage = *data.get(linkage_pos).ok_or(linkage_pos + 1)?;
+    let len = linkage_pos + 1 + CellLinkage::data_len(linkage);
+    if len > data.len() {
+        Err(len)
+    } else {
+        Ok(len)
+    }
+}
+
+/// The position of a [`LogicalCell`] within the grid of elementary cells of a mosaic, as
+/// returned by [`MosaicDescriptor::cell_bounds()`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CellBounds {
+    pub column: u8,
+    pub row: u8,
+    pub columns: u8,
+    pub rows: u8,
+}
+
+/// Describes the layout of a mosaic service, and the services or events presented by each part
+/// of the screen, as defined in _ETSI EN 300 468_ section 6.2.21.
+pub struct MosaicDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MosaicDescriptor<'buf> {
+    pub const TAG: u8 = 0x51;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<MosaicDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        let mut pos = 1;
+        while pos < data.len() {
+            match logical_cell_len(&data[pos..]) {
+                Ok(len) => pos += len,
+                Err(required) => {
+                    return Err(descriptor::DescriptorError::NotEnoughData {
+                        tag,
+                        actual: data.len(),
+                        expected: pos + required,
+                    })
+                }
+            }
+        }
+        Ok(MosaicDescriptor { data })
+    }
+
+    /// `true` if this is the top-level mosaic of a hierarchy of mosaics
+    pub fn mosaic_entry_point(&self) -> bool {
+        self.data[0] & 0b1000_0000 != 0
+    }
+
+    /// Number of elementary cells across the screen (1 to 8)
+    pub fn number_of_horizontal_elementary_cells(&self) -> u8 {
+        ((self.data[0] >> 4) & 0b111) + 1
+    }
+
+    /// Number of elementary cells down the screen (1 to 8)
+    pub fn number_of_vertical_elementary_cells(&self) -> u8 {
+        (self.data[0] & 0b111) + 1
+    }
+
+    pub fn logical_cells(&self) -> impl Iterator<Item = LogicalCell<'buf>> {
+        let mut remaining = &self.data[1..];
+        std::iter::from_fn(move || {
+            if remaining.is_empty() {
+                return None;
+            }
+            // lengths were checked by MosaicDescriptor::new()
+            let (head, tail) = remaining.split_at(logical_cell_len(remaining).unwrap());
+            remaining = tail;
+            Some(LogicalCell { data: head })
+        })
+    }
+
+    /// The smallest rectangle of elementary cells containing all those making up the given
+    /// logical cell, or `None` if the logical cell has no elementary cells within the grid.
+    pub fn cell_bounds(&self, cell: &LogicalCell<'_>) -> Option<CellBounds> {
+        let width = self.number_of_horizontal_elementary_cells();
+        let height = self.number_of_vertical_elementary_cells();
+        let positions = cell
+            .elementary_cell_ids()
+            .filter(|id| *id < width * height)
+            .map(|id| (id % width, id / width));
+        let (min_col, min_row, max_col, max_row) =
+            positions.fold(None, |acc, (col, row)| match acc {
+                None => Some((col, row, col, row)),
+                Some((c0, r0, c1, r1)) => {
+                    Some((c0.min(col), r0.min(row), c1.max(col), r1.max(row)))
+                }
+            })?;
+        Some(CellBounds {
+            column: min_col,
+            row: min_row,
+            columns: max_col - min_col + 1,
+            rows: max_row - min_row + 1,
+        })
+    }
+
+    /// The `logical_cell_id` occupying each elementary cell, indexed by row and then column, or
+    /// `None` for elementary cells not belonging to any logical cell.
+    pub fn grid(&self) -> Vec<Vec<Option<u8>>> {
+        let width = self.number_of_horizontal_elementary_cells();
+        let height = self.number_of_vertical_elementary_cells();
+        let mut grid = vec![vec![None; usize::from(width)]; usize::from(height)];
+        for cell in self.logical_cells() {
+            for id in cell.elementary_cell_ids() {
+                if id < width * height {
+                    grid[usize::from(id / width)][usize::from(id % width)] =
+                        Some(cell.logical_cell_id());
+                }
+            }
+        }
+        grid
+    }
+}
+impl<'buf> fmt::Debug for MosaicDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MosaicDescriptor")
+            .field("mosaic_entry_point", &self.mosaic_entry_point())
+            .field(
+                "number_of_horizontal_elementary_cells",
+                &self.number_of_horizontal_elementary_cells(),
+            )
+            .field(
+                "number_of_vertical_elementary_cells",
+                &self.number_of_vertical_elementary_cells(),
+            )
+            .field("logical_cells", &self.logical_cells().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 3x2 grid: a large cell on the left (elementary cells 0, 1, 3, 4), two small cells on the
+    // right (2 and 5)
+    #[rustfmt::skip]
+    const DATA: [u8; 35] = [
+        0b1010_0001,
+        // logical cell 0: video, linked to a service
+        0 << 2, 0b1111_1001, 0x04, 0x00, 0x01, 0x03, 0x04,
+        0x02, 0x00, 0x01, 0x00, 0x02, 0x10, 0x01,
+        // logical cell 1: still picture, linked to an event
+        1 << 2, 0b1111_1010, 0x01, 0x02,
+        0x04, 0x00, 0x01, 0x00, 0x02, 0x10, 0x02, 0x12, 0x34,
+        // logical cell 2: graphics, linked to a bouquet
+        2 << 2, 0b1111_1011, 0x01, 0x05,
+        0x01, 0x00, 0x10,
+    ];
+
+    #[test]
+    fn mosaic_descriptor() {
+        let desc = MosaicDescriptor::new(0x51, &DATA).unwrap();
+        assert!(desc.mosaic_entry_point());
+        assert_eq!(3, desc.number_of_horizontal_elementary_cells());
+        assert_eq!(2, desc.number_of_vertical_elementary_cells());
+        let cells: Vec<_> = desc.logical_cells().collect();
+        assert_eq!(3, cells.len());
+        assert_eq!(0, cells[0].logical_cell_id());
+        assert_eq!(
+            CellPresentationInfo::Video,
+            cells[0].logical_cell_presentation_info()
+        );
+        assert_eq!(
+            vec![0, 1, 3, 4],
+            cells[0].elementary_cell_ids().collect::<Vec<_>>()
+        );
+        let service = ServiceTriplet {
+            original_network_id: 1,
+            transport_stream_id: 2,
+            service_id: 0x1001,
+        };
+        assert_eq!(CellLinkage::Service(service), cells[0].cell_linkage());
+        assert_eq!(
+            CellLinkage::Event {
+                service: ServiceTriplet {
+                    service_id: 0x1002,
+                    ..service
+                },
+                event_id: 0x1234,
+            },
+            cells[1].cell_linkage()
+        );
+        assert_eq!(
+            CellLinkage::Bouquet { bouquet_id: 0x10 },
+            cells[2].cell_linkage()
+        );
+        let expected = |len| match MosaicDescriptor::new(0x51, &DATA[..len]) {
+            Err(descriptor::DescriptorError::NotEnoughData { expected, .. }) => expected,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(18, expected(16));
+        assert_eq!(20, expected(19));
+        assert_eq!(31, expected(29));
+        assert_eq!(35, expected(34));
+    }
+
+    #[test]
+    fn layout() {
+        let desc = MosaicDescriptor::new(0x51, &DATA).unwrap();
+        assert_eq!(
+            vec![
+                vec![Some(0), Some(0), Some(1)],
+                vec![Some(0), Some(0), Some(2)],
+            ],
+            desc.grid()
+        );
+        let cells: Vec<_> = desc.logical_cells().collect();
+        assert_eq!(
+            Some(CellBounds {
+                column: 0,
+                row: 0,
+                columns: 2,
+                rows: 2
+            }),
+            desc.cell_bounds(&cells[0])
+        );
+        assert_eq!(
+            Some(CellBounds {
+                column: 2,
+                row: 1,
+                columns: 1,
+                rows: 1
+            }),
+            desc.cell_bounds(&cells[2])
+        );
+    }
+}
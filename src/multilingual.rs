@@ -0,0 +1,391 @@
+//! _Multilingual Network Name_ (tag 0x5B), _Multilingual Bouquet Name_ (tag 0x5C),
+//! _Multilingual Service Name_ (tag 0x5D) and _Multilingual Component_ (tag 0x5E) descriptors
+use crate::{Text, TextError};
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Length of the entry at the start of `data`, being a 3-byte language code followed by
+/// `text_fields` length-prefixed strings.  Returns `Err` with the number of bytes the entry
+/// requires if `data` is too short.
+fn entry_len(data: &[u8], text_fields: usize) -> Result<usize, usize> {
+    let mut len = 3;
+    for _ in 0..text_fields {
+        if len >= data.len() {
+            return Err(len + 1);
+        }
+        len += 1 + usize::from(data[len]);
+    }
+    if len > data.len() {
+        Err(len)
+    } else {
+        Ok(len)
+    }
+}
+
+/// Checks that the entries following the first `start` bytes of `data` are complete.
+fn check_entries(
+    tag: u8,
+    data: &[u8],
+    start: usize,
+    text_fields: usize,
+) -> Result<(), descriptor::DescriptorError> {
+    let mut pos = start;
+    while pos < data.len() {
+        match entry_len(&data[pos..], text_fields) {
+            Ok(len) => pos += len,
+            Err(expected) => {
+                return Err(descriptor::DescriptorError::NotEnoughData {
+                    tag,
+                    actual: data.len(),
+                    expected: pos + expected,
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+struct EntryIterator<'buf> {
+    remaining_data: &'buf [u8],
+    text_fields: usize,
+}
+impl<'buf> Iterator for EntryIterator<'buf> {
+    type Item = &'buf [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = entry_len(self.remaining_data, self.text_fields).ok()?;
+        let (head, tail) = self.remaining_data.split_at(len);
+        self.remaining_data = tail;
+        Some(head)
+    }
+}
+
+pub(crate) fn language_matches(language_code: &[u8], language: &str) -> bool {
+    language_code.eq_ignore_ascii_case(language.as_bytes())
+}
+
+/// A name (or description) in one language.
+pub struct MultilingualName<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MultilingualName<'buf> {
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn name(&self) -> Result<Text<'buf>, TextError> {
+        let (text, _) = Text::read(&self.data[3..])?;
+        Ok(text)
+    }
+}
+impl<'buf> fmt::Debug for MultilingualName<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MultilingualName")
+            .field("language_code", &self.language_code_str())
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+fn names(data: &[u8]) -> impl Iterator<Item = MultilingualName<'_>> {
+    EntryIterator {
+        remaining_data: data,
+        text_fields: 1,
+    }
+    .map(|data| MultilingualName { data })
+}
+
+fn find_name<'buf>(
+    mut names: impl Iterator<Item = MultilingualName<'buf>>,
+    language: &str,
+) -> Option<Text<'buf>> {
+    names
+        .find(|n| language_matches(n.language_code(), language))
+        .and_then(|n| n.name().ok())
+}
+
+/// Gives the name of a network in one or more languages, as defined in _ETSI EN 300 468_
+/// section 6.2.24.
+pub struct MultilingualNetworkNameDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MultilingualNetworkNameDescriptor<'buf> {
+    pub const TAG: u8 = 0x5B;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<MultilingualNetworkNameDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        check_entries(tag, data, 0, 1)?;
+        Ok(MultilingualNetworkNameDescriptor { data })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = MultilingualName<'buf>> {
+        names(self.data)
+    }
+
+    /// The network name for the given ISO 639-2 language code (compared case-insensitively),
+    /// if present.
+    pub fn name(&self, language: &str) -> Option<Text<'buf>> {
+        find_name(self.names(), language)
+    }
+}
+impl<'buf> fmt::Debug for MultilingualNetworkNameDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MultilingualNetworkNameDescriptor")
+            .field("names", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Gives the name of a bouquet in one or more languages, as defined in _ETSI EN 300 468_
+/// section 6.2.22.
+pub struct MultilingualBouquetNameDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MultilingualBouquetNameDescriptor<'buf> {
+    pub const TAG: u8 = 0x5C;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<MultilingualBouquetNameDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        check_entries(tag, data, 0, 1)?;
+        Ok(MultilingualBouquetNameDescriptor { data })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = MultilingualName<'buf>> {
+        names(self.data)
+    }
+
+    /// The bouquet name for the given ISO 639-2 language code (compared case-insensitively),
+    /// if present.
+    pub fn name(&self, language: &str) -> Option<Text<'buf>> {
+        find_name(self.names(), language)
+    }
+}
+impl<'buf> fmt::Debug for MultilingualBouquetNameDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MultilingualBouquetNameDescriptor")
+            .field("names", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A service provider name and service name in one language.
+pub struct MultilingualServiceName<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MultilingualServiceName<'buf> {
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn service_provider_name(&self) -> Result<Text<'buf>, TextError> {
+        let (text, _) = Text::read(&self.data[3..])?;
+        Ok(text)
+    }
+
+    pub fn service_name(&self) -> Result<Text<'buf>, TextError> {
+        let (_, consumed) = Text::read(&self.data[3..])?;
+        let (text, _) = Text::read(&self.data[3 + consumed..])?;
+        Ok(text)
+    }
+}
+impl<'buf> fmt::Debug for MultilingualServiceName<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MultilingualServiceName")
+            .field("language_code", &self.language_code_str())
+            .field("service_provider_name", &self.service_provider_name())
+            .field("service_name", &self.service_name())
+            .finish()
+    }
+}
+
+/// Gives the service provider name and service name in one or more languages, as defined in
+/// _ETSI EN 300 468_ section 6.2.25.
+pub struct MultilingualServiceNameDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MultilingualServiceNameDescriptor<'buf> {
+    pub const TAG: u8 = 0x5D;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<MultilingualServiceNameDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        check_entries(tag, data, 0, 2)?;
+        Ok(MultilingualServiceNameDescriptor { data })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = MultilingualServiceName<'buf>> {
+        EntryIterator {
+            remaining_data: self.data,
+            text_fields: 2,
+        }
+        .map(|data| MultilingualServiceName { data })
+    }
+
+    /// The entry for the given ISO 639-2 language code (compared case-insensitively), if
+    /// present.
+    pub fn name(&self, language: &str) -> Option<MultilingualServiceName<'buf>> {
+        self.names()
+            .find(|n| language_matches(n.language_code(), language))
+    }
+}
+impl<'buf> fmt::Debug for MultilingualServiceNameDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MultilingualServiceNameDescriptor")
+            .field("names", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Gives a description of a component in one or more languages, as defined in
+/// _ETSI EN 300 468_ section 6.2.23.  The component is identified by the same `component_tag`
+/// as its _Component Descriptor_ and _Stream Identifier Descriptor_.
+pub struct MultilingualComponentDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MultilingualComponentDescriptor<'buf> {
+    pub const TAG: u8 = 0x5E;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<MultilingualComponentDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        check_entries(tag, data, 1, 1)?;
+        Ok(MultilingualComponentDescriptor { data })
+    }
+
+    pub fn component_tag(&self) -> u8 {
+        self.data[0]
+    }
+
+    pub fn text_descriptions(&self) -> impl Iterator<Item = MultilingualName<'buf>> {
+        names(&self.data[1..])
+    }
+
+    /// The text description for the given ISO 639-2 language code (compared
+    /// case-insensitively), if present.
+    pub fn text_description(&self, language: &str) -> Option<Text<'buf>> {
+        find_name(self.text_descriptions(), language)
+    }
+}
+impl<'buf> fmt::Debug for MultilingualComponentDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MultilingualComponentDescriptor")
+            .field("component_tag", &self.component_tag())
+            .field(
+                "text_descriptions",
+                &self.text_descriptions().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn multilingual_network_name() {
+        #[rustfmt::skip]
+        let data = [
+            b'd', b'e', b'u', 0x03, b'S', b'R', b'G',
+            b'f', b'r', b'a', 0x03, b'S', b'S', b'R',
+        ];
+        let desc = MultilingualNetworkNameDescriptor::new(0x5B, &data).unwrap();
+        let names: Vec<_> = desc.names().collect();
+        assert_eq!(2, names.len());
+        assert_eq!(Some("deu"), names[0].language_code_str());
+        assert_eq!("SRG", names[0].name().unwrap().to_string().unwrap());
+        assert_eq!("SSR", desc.name("FRA").unwrap().to_string().unwrap());
+        assert!(desc.name("ita").is_none());
+    }
+
+    #[test]
+    fn multilingual_service_name() {
+        #[rustfmt::skip]
+        let data = [
+            b'n', b'l', b'd', 0x03, b'V', b'R', b'T', 0x03, b'E', b'e', b'n',
+            b'f', b'r', b'a', 0x04, b'R', b'T', b'B', b'F', 0x00,
+        ];
+        let desc = MultilingualServiceNameDescriptor::new(0x5D, &data).unwrap();
+        let nld = desc.name("nld").unwrap();
+        assert_eq!(
+            "VRT",
+            nld.service_provider_name().unwrap().to_string().unwrap()
+        );
+        assert_eq!("Een", nld.service_name().unwrap().to_string().unwrap());
+        let fra = desc.name("fra").unwrap();
+        assert_eq!(
+            "RTBF",
+            fra.service_provider_name().unwrap().to_string().unwrap()
+        );
+        assert_eq!("", fra.service_name().unwrap().to_string().unwrap());
+    }
+
+    #[test]
+    fn multilingual_component() {
+        #[rustfmt::skip]
+        let data = [
+            0x02, // component_tag
+            b's', b'w', b'e', 0x05, b'S', b'v', b'e', b'n', b's',
+            b'f', b'i', b'n', 0x05, b'S', b'u', b'o', b'm', b'i',
+        ];
+        let desc = MultilingualComponentDescriptor::new(0x5E, &data).unwrap();
+        assert_eq!(2, desc.component_tag());
+        assert_eq!(2, desc.text_descriptions().count());
+        assert_eq!(
+            "Suomi",
+            desc.text_description("fin").unwrap().to_string().unwrap()
+        );
+    }
+
+    #[test]
+    fn truncated_entry() {
+        let data = [b'e', b'n', b'g', 0x05, b'A', b'B'];
+        assert!(matches!(
+            MultilingualBouquetNameDescriptor::new(0x5C, &data),
+            Err(descriptor::DescriptorError::NotEnoughData {
+                tag: 0x5C,
+                actual: 6,
+                expected: 9
+            })
+        ));
+        assert!(matches!(
+            MultilingualComponentDescriptor::new(0x5E, &[0x01, b'e', b'n']),
+            Err(descriptor::DescriptorError::NotEnoughData {
+                tag: 0x5E,
+                actual: 3,
+                expected: 5
+            })
+        ));
+    }
+}
@@ -0,0 +1,499 @@
+//! _Network Change Notify Descriptor_ (extension tag 0x07) and _Message Descriptor_ (extension
+//! tag 0x08)
+//!
+//! A broadcaster announces planned changes to its network (such as a multiplex changing
+//! frequency, after which receivers will need to retune) using the network change notify
+//! descriptor in the NIT, optionally referring to an explanatory message for viewers given by a
+//! message descriptor.  See [`upcoming_notices()`].
+use crate::eit::BcdDuration;
+use crate::extension::ExtensionDescriptors;
+use crate::multilingual::language_matches;
+use crate::time::{MjdTimestamp, MjdTimestampError};
+use crate::{En300_468Descriptors, Text, TextError};
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// A text message from the broadcaster, referenced by its `message_id`, as defined in
+/// _ETSI EN 300 468_ section 6.4.8.
+///
+/// This is carried within an
+/// [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct MessageDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> MessageDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x08;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<MessageDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        if data.len() < 4 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected: 4,
+            });
+        }
+        Ok(MessageDescriptor { data })
+    }
+
+    pub fn message_id(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[1..4]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn text(&self) -> Result<Text<'buf>, TextError> {
+        Text::new(&self.data[4..])
+    }
+}
+impl<'buf> fmt::Debug for MessageDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MessageDescriptor")
+            .field("message_id", &self.message_id())
+            .field("language_code", &self.language_code_str())
+            .field("text", &self.text())
+            .finish()
+    }
+}
+
+/// The receivers affected by a [`NetworkChange`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReceiverCategory {
+    AllReceivers,
+    /// Only DVB-T2, DVB-S2 or DVB-C2 capable receivers
+    SecondGenerationReceivers,
+    Reserved(u8),
+}
+impl ReceiverCategory {
+    pub fn from_id(id: u8) -> ReceiverCategory {
+        match id {
+            0 => ReceiverCategory::AllReceivers,
+            1 => ReceiverCategory::SecondGenerationReceivers,
+            2..=7 => ReceiverCategory::Reserved(id),
+            _ => panic!(
+                "Invalid receiver_category value {} (must be less than 8)",
+                id
+            ),
+        }
+    }
+}
+
+/// The kind of a [`NetworkChange`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChangeType {
+    /// No change to the network; only a message is to be shown
+    MessageOnly,
+    MinorDefault,
+    MinorMultiplexRemoved,
+    MinorServiceChanged,
+    MinorReserved(u8),
+    MajorDefault,
+    MajorMultiplexFrequencyChanged,
+    MajorMultiplexCoverageChanged,
+    MajorMultiplexAdded,
+    MajorReserved(u8),
+}
+impl ChangeType {
+    pub fn from_id(id: u8) -> ChangeType {
+        match id {
+            0x0 => ChangeType::MessageOnly,
+            0x1 => ChangeType::MinorDefault,
+            0x2 => ChangeType::MinorMultiplexRemoved,
+            0x3 => ChangeType::MinorServiceChanged,
+            0x4..=0x7 => ChangeType::MinorReserved(id),
+            0x8 => ChangeType::MajorDefault,
+            0x9 => ChangeType::MajorMultiplexFrequencyChanged,
+            0xA => ChangeType::MajorMultiplexCoverageChanged,
+            0xB => ChangeType::MajorMultiplexAdded,
+            0xC..=0xF => ChangeType::MajorReserved(id),
+            _ => panic!("Invalid change_type value {} (must be less than 16)", id),
+        }
+    }
+
+    /// `true` for major changes, after which receivers are likely to need to rescan
+    pub fn is_major(&self) -> bool {
+        matches!(
+            self,
+            ChangeType::MajorDefault
+                | ChangeType::MajorMultiplexFrequencyChanged
+                | ChangeType::MajorMultiplexCoverageChanged
+                | ChangeType::MajorMultiplexAdded
+                | ChangeType::MajorReserved(_)
+        )
+    }
+}
+
+/// A transport stream which is unaffected by a [`NetworkChange`], and which receivers may
+/// therefore remain tuned to
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvariantTs {
+    pub transport_stream_id: u16,
+    pub original_network_id: u16,
+}
+
+/// One planned change from a [`NetworkChangeNotifyDescriptor`]
+pub struct NetworkChange<'buf> {
+    cell_id: u16,
+    data: &'buf [u8],
+}
+impl<'buf> NetworkChange<'buf> {
+    const SIZE: usize = 12;
+
+    /// The cell affected by the change, or `0` if the change is not specific to a cell
+    pub fn cell_id(&self) -> u16 {
+        self.cell_id
+    }
+
+    pub fn network_change_id(&self) -> u8 {
+        self.data[0]
+    }
+
+    pub fn network_change_version(&self) -> u8 {
+        self.data[1]
+    }
+
+    pub fn start_time_of_change(&self) -> Result<MjdTimestamp<'buf>, MjdTimestampError> {
+        MjdTimestamp::new(&self.data[2..7])
+    }
+
+    /// How long the change will take, during which services may be interrupted
+    pub fn change_duration(&self) -> BcdDuration {
+        BcdDuration::new(
+            u32::from(self.data[7]) << 16 | u32::from(self.data[8]) << 8 | u32::from(self.data[9]),
+        )
+    }
+
+    pub fn receiver_category(&self) -> ReceiverCategory {
+        ReceiverCategory::from_id(self.data[10] >> 5)
+    }
+
+    fn invariant_ts_present(&self) -> bool {
+        self.data[10] & 0b1_0000 != 0
+    }
+
+    pub fn change_type(&self) -> ChangeType {
+        ChangeType::from_id(self.data[10] & 0b1111)
+    }
+
+    /// Identifies the [`MessageDescriptor`] giving a message to accompany this change
+    pub fn message_id(&self) -> u8 {
+        self.data[11]
+    }
+
+    pub fn invariant_ts(&self) -> Option<InvariantTs> {
+        if !self.invariant_ts_present() {
+            return None;
+        }
+        Some(InvariantTs {
+            transport_stream_id: u16::from(self.data[12]) << 8 | u16::from(self.data[13]),
+            original_network_id: u16::from(self.data[14]) << 8 | u16::from(self.data[15]),
+        })
+    }
+}
+impl<'buf> fmt::Debug for NetworkChange<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("NetworkChange")
+            .field("cell_id", &self.cell_id())
+            .field("network_change_id", &self.network_change_id())
+            .field("network_change_version", &self.network_change_version())
+            .field("start_time_of_change", &self.start_time_of_change())
+            .field("change_duration", &self.change_duration())
+            .field("receiver_category", &self.receiver_category())
+            .field("change_type", &self.change_type())
+            .field("message_id", &self.message_id())
+            .field("invariant_ts", &self.invariant_ts())
+            .finish()
+    }
+}
+
+fn network_change_len(data: &[u8]) -> usize {
+    if data[10] & 0b1_0000 != 0 {
+        NetworkChange::SIZE + 4
+    } else {
+        NetworkChange::SIZE
+    }
+}
+
+/// Checks the inner loop of changes for one cell, failing if a change overruns the loop
+fn check_changes(mut data: &[u8]) -> Result<(), descriptor::DescriptorError> {
+    while !data.is_empty() {
+        let len = if data.len() > 10 {
+            network_change_len(data)
+        } else {
+            NetworkChange::SIZE
+        };
+        if data.len() < len {
+            return Err(descriptor::DescriptorError::TagTooLongForBuffer {
+                taglen: len,
+                remaining_len: data.len(),
+            });
+        }
+        data = &data[len..];
+    }
+    Ok(())
+}
+
+/// Announces planned changes to the network, as defined in _ETSI EN 300 468_ section 6.4.9.
+///
+/// This is carried within an
+/// [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct NetworkChangeNotifyDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> NetworkChangeNotifyDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x07;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<NetworkChangeNotifyDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        let mut pos = 0;
+        while pos < data.len() {
+            let end = match data.get(pos + 2) {
+                Some(loop_length) => pos + 3 + usize::from(*loop_length),
+                None => pos + 3,
+            };
+            if end > data.len() {
+                return Err(descriptor::DescriptorError::NotEnoughData {
+                    tag: crate::extension::ExtensionDescriptor::TAG,
+                    actual: data.len(),
+                    expected: end,
+                });
+            }
+            check_changes(&data[pos + 3..end])?;
+            pos = end;
+        }
+        Ok(NetworkChangeNotifyDescriptor { data })
+    }
+
+    /// All announced changes, for all cells
+    pub fn changes(&self) -> impl Iterator<Item = NetworkChange<'buf>> + 'buf {
+        let mut remaining = self.data;
+        let mut cell: &[u8] = &[];
+        let mut cell_id = 0;
+        std::iter::from_fn(move || {
+            // lengths were checked by NetworkChangeNotifyDescriptor::new()
+            while cell.is_empty() {
+                if remaining.is_empty() {
+                    return None;
+                }
+                cell_id = u16::from(remaining[0]) << 8 | u16::from(remaining[1]);
+                let end = 3 + usize::from(remaining[2]);
+                cell = &remaining[3..end];
+                remaining = &remaining[end..];
+            }
+            let (head, tail) = cell.split_at(network_change_len(cell));
+            cell = tail;
+            Some(NetworkChange {
+                cell_id,
+                data: head,
+            })
+        })
+    }
+}
+impl<'buf> fmt::Debug for NetworkChangeNotifyDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("NetworkChangeNotifyDescriptor")
+            .field("changes", &self.changes().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A planned network change, together with the broadcaster's message about it (if any), as
+/// produced by [`upcoming_notices()`]
+#[derive(Debug)]
+pub struct NetworkChangeNotice<'buf> {
+    pub change: NetworkChange<'buf>,
+    pub message: Option<MessageDescriptor<'buf>>,
+}
+impl<'buf> NetworkChangeNotice<'buf> {
+    /// The time at which the change starts, in seconds since the Unix epoch
+    pub fn start(&self) -> i64 {
+        // only changes with valid start times are included in notices
+        self.change
+            .start_time_of_change()
+            .unwrap()
+            .to_unix_timestamp()
+    }
+
+    /// The time by which the change will be complete, in seconds since the Unix epoch
+    pub fn end(&self) -> i64 {
+        self.start() + i64::from(self.change.change_duration().as_seconds())
+    }
+}
+
+/// Lists the network changes announced in the given descriptor loop (normally the first
+/// descriptor loop of the NIT) which are not yet complete at the time `now` (in seconds since
+/// the Unix epoch), ordered by their start time.
+///
+/// Each change is accompanied by the message descriptor from the same loop having the change's
+/// `message_id`, in the first of the `preferred_languages` (three-character ISO 639-2 codes,
+/// compared case-insensitively) that is available, or otherwise in any available language.
+///
+/// Changes having an invalid `start_time_of_change` are omitted.
+pub fn upcoming_notices<'buf>(
+    descriptors: impl IntoIterator<
+        Item = Result<En300_468Descriptors<'buf>, descriptor::DescriptorError>,
+    >,
+    now: i64,
+    preferred_languages: &[&str],
+) -> Vec<NetworkChangeNotice<'buf>> {
+    let mut changes = vec![];
+    let mut messages = vec![];
+    for desc in descriptors.into_iter().flatten() {
+        if let En300_468Descriptors::Extension(ext) = desc {
+            match ext.descriptor() {
+                Ok(ExtensionDescriptors::NetworkChangeNotify(d)) => changes.extend(d.changes()),
+                Ok(ExtensionDescriptors::Message(d)) => messages.push(d),
+                _ => (),
+            }
+        }
+    }
+    let mut notices: Vec<_> = changes
+        .into_iter()
+        .filter(|c| c.start_time_of_change().is_ok())
+        .map(|change| {
+            let candidates: Vec<_> = messages
+                .iter()
+                .filter(|m| m.message_id() == change.message_id())
+                .collect();
+            let message = preferred_languages
+                .iter()
+                .find_map(|pref| {
+                    candidates
+                        .iter()
+                        .find(|m| language_matches(m.language_code(), pref))
+                })
+                .or_else(|| candidates.first())
+                .map(|m| MessageDescriptor { data: m.data });
+            NetworkChangeNotice { change, message }
+        })
+        .filter(|n| n.end() > now)
+        .collect();
+    notices.sort_by_key(|n| n.start());
+    notices
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[rustfmt::skip]
+    const LOOP: [u8; 59] = [
+        // network_change_notify_descriptor
+        0x7F, 0x23, 0x07,
+        // cell 0x0001
+        0x00, 0x01, 0x1C,
+        // 2024-03-31 02:00:00 for 01:30:00, all receivers, frequency change, message 1,
+        // TS 0x1000 of network 0x233A unaffected
+        0x01, 0x00, 0xEB, 0xF0, 0x02, 0x00, 0x00, 0x01, 0x30, 0x00, 0b0001_1001, 0x01,
+        0x10, 0x00, 0x23, 0x3A,
+        // 2024-03-30 01:00:00 for 00:10:00, second generation receivers, minor, message 2
+        0x02, 0x00, 0xEB, 0xEF, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0b0010_0001, 0x02,
+        // cell 0x0002, no changes
+        0x00, 0x02, 0x00,
+        // message_descriptor
+        0x7F, 0x09, 0x08, 0x01, b'w', b'e', b'l', b'R', b'h', b'i', b'f',
+        0x7F, 0x09, 0x08, 0x01, b'e', b'n', b'g', b'R', b'e', b't', b'u',
+    ];
+
+    #[test]
+    fn network_change_notify_descriptor() {
+        let desc = NetworkChangeNotifyDescriptor::new(0x07, &LOOP[3..37]).unwrap();
+        let changes: Vec<_> = desc.changes().collect();
+        assert_eq!(2, changes.len());
+        assert_eq!(1, changes[0].cell_id());
+        assert_eq!(
+            (2024, 3, 31),
+            changes[0].start_time_of_change().unwrap().date()
+        );
+        assert_eq!(5400, changes[0].change_duration().as_seconds());
+        assert_eq!(
+            ReceiverCategory::AllReceivers,
+            changes[0].receiver_category()
+        );
+        assert_eq!(
+            ChangeType::MajorMultiplexFrequencyChanged,
+            changes[0].change_type()
+        );
+        assert!(changes[0].change_type().is_major());
+        assert_eq!(
+            Some(InvariantTs {
+                transport_stream_id: 0x1000,
+                original_network_id: 0x233A,
+            }),
+            changes[0].invariant_ts()
+        );
+        assert_eq!(
+            ReceiverCategory::SecondGenerationReceivers,
+            changes[1].receiver_category()
+        );
+        assert_eq!(None, changes[1].invariant_ts());
+        let expected = |end| match NetworkChangeNotifyDescriptor::new(0x07, &LOOP[3..end]) {
+            Err(descriptor::DescriptorError::NotEnoughData { expected, .. }) => expected,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(3, expected(5));
+        assert_eq!(31, expected(10));
+        assert_eq!(34, expected(36));
+        // a change overrunning the cell's loop_length
+        assert!(matches!(
+            NetworkChangeNotifyDescriptor::new(
+                0x07,
+                &[0x00, 0x01, 0x05, 0x01, 0x00, 0xEB, 0xF0, 0x02]
+            ),
+            Err(descriptor::DescriptorError::TagTooLongForBuffer {
+                taglen: 12,
+                remaining_len: 5,
+            })
+        ));
+    }
+
+    #[test]
+    fn notices() {
+        let start = changes_start();
+        let notices = upcoming_notices(descriptor::DescriptorIter::new(&LOOP[..]), 0, &["eng"]);
+        assert_eq!(2, notices.len());
+        assert_eq!(2, notices[0].change.message_id());
+        assert!(notices[0].message.is_none());
+        let message = notices[1].message.as_ref().unwrap();
+        assert_eq!(Some("eng"), message.language_code_str());
+        assert_eq!(start, notices[1].start());
+
+        // the first change has completed, and the welsh message is preferred
+        let notices = upcoming_notices(
+            descriptor::DescriptorIter::new(&LOOP[..]),
+            start - 1,
+            &["wel", "eng"],
+        );
+        assert_eq!(1, notices.len());
+        let message = notices[0].message.as_ref().unwrap();
+        assert_eq!("Rhif", message.text().unwrap().to_string().unwrap());
+
+        assert!(upcoming_notices(
+            descriptor::DescriptorIter::new(&LOOP[..]),
+            start + 5400,
+            &[]
+        )
+        .is_empty());
+    }
+
+    fn changes_start() -> i64 {
+        MjdTimestamp::new(&[0xEB, 0xF0, 0x02, 0x00, 0x00])
+            .unwrap()
+            .to_unix_timestamp()
+    }
+}
@@ -0,0 +1,350 @@
+//! _Near Video On Demand_ support: the _NVOD Reference_ (tag 0x4B), _Time Shifted Service_
+//! (tag 0x4C) and _Time Shifted Event_ (tag 0x4F) descriptors, plus [`NvodResolver`] for looking
+//! up the event information that time-shifted services leave out.
+use crate::eit::{EitSection, Event};
+use crate::sdt::SdtSection;
+use crate::{En300_468Descriptors, ServiceTriplet};
+use mpeg2ts_reader::descriptor;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Lists the time-shifted services that make up an NVOD service, as defined in
+/// _ETSI EN 300 468_ section 6.2.26.  Found in the SDT entry of the NVOD reference service.
+pub struct NvodReferenceDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> NvodReferenceDescriptor<'buf> {
+    pub const TAG: u8 = 0x4B;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<NvodReferenceDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(NvodReferenceDescriptor { data })
+    }
+
+    /// The time-shifted services belonging to this NVOD reference service
+    pub fn services(&self) -> impl Iterator<Item = ServiceTriplet> + 'buf {
+        self.data.chunks_exact(6).map(|d| ServiceTriplet {
+            transport_stream_id: u16::from(d[0]) << 8 | u16::from(d[1]),
+            original_network_id: u16::from(d[2]) << 8 | u16::from(d[3]),
+            service_id: u16::from(d[4]) << 8 | u16::from(d[5]),
+        })
+    }
+}
+impl<'buf> fmt::Debug for NvodReferenceDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("NvodReferenceDescriptor")
+            .field("services", &self.services().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Links a time-shifted service to its NVOD reference service, as defined in
+/// _ETSI EN 300 468_ section 6.2.45.
+pub struct TimeShiftedServiceDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TimeShiftedServiceDescriptor<'buf> {
+    pub const TAG: u8 = 0x4C;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<TimeShiftedServiceDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 2 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 2,
+            });
+        }
+        Ok(TimeShiftedServiceDescriptor { data })
+    }
+
+    pub fn reference_service_id(&self) -> u16 {
+        u16::from(self.data[0]) << 8 | u16::from(self.data[1])
+    }
+}
+impl<'buf> fmt::Debug for TimeShiftedServiceDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TimeShiftedServiceDescriptor")
+            .field("reference_service_id", &self.reference_service_id())
+            .finish()
+    }
+}
+
+/// Links an event of a time-shifted service to the event of the NVOD reference service which
+/// holds its description, as defined in _ETSI EN 300 468_ section 6.2.44.
+pub struct TimeShiftedEventDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TimeShiftedEventDescriptor<'buf> {
+    pub const TAG: u8 = 0x4F;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<TimeShiftedEventDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 4 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 4,
+            });
+        }
+        Ok(TimeShiftedEventDescriptor { data })
+    }
+
+    pub fn reference_service_id(&self) -> u16 {
+        u16::from(self.data[0]) << 8 | u16::from(self.data[1])
+    }
+
+    pub fn reference_event_id(&self) -> u16 {
+        u16::from(self.data[2]) << 8 | u16::from(self.data[3])
+    }
+}
+impl<'buf> fmt::Debug for TimeShiftedEventDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TimeShiftedEventDescriptor")
+            .field("reference_service_id", &self.reference_service_id())
+            .field("reference_event_id", &self.reference_event_id())
+            .finish()
+    }
+}
+
+/// The description of an event of an NVOD reference service, as returned by
+/// [`NvodResolver::resolve()`].
+pub struct ReferenceEvent<'a> {
+    service_id: u16,
+    event_id: u16,
+    descriptors_data: &'a [u8],
+}
+impl<'a> ReferenceEvent<'a> {
+    pub fn service_id(&self) -> u16 {
+        self.service_id
+    }
+
+    pub fn event_id(&self) -> u16 {
+        self.event_id
+    }
+
+    /// The descriptors of the reference event (e.g. its
+    /// [`ShortEventDescriptor`](../short_event/struct.ShortEventDescriptor.html)).
+    pub fn descriptors<Desc: descriptor::Descriptor<'a>>(
+        &self,
+    ) -> descriptor::DescriptorIter<'a, Desc> {
+        descriptor::DescriptorIter::new(self.descriptors_data)
+    }
+}
+impl<'a> fmt::Debug for ReferenceEvent<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ReferenceEvent")
+            .field("service_id", &self.service_id())
+            .field("event_id", &self.event_id())
+            .field(
+                "descriptors",
+                &self
+                    .descriptors::<En300_468Descriptors<'_>>()
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Gathers NVOD signalling from SDT and EIT sections so that the events of time-shifted
+/// services, which normally carry only a [`TimeShiftedEventDescriptor`], can be presented with
+/// the name and description held by the corresponding event of the NVOD reference service.
+///
+/// Services are identified by `(original_network_id, service_id)`, since the reference service
+/// and its time-shifted services always belong to the same original network.
+///
+/// Events of a reference service are only retained once the SDT has identified that service as
+/// an NVOD reference service, so SDT sections should be passed to
+/// [`add_sdt_section()`](#method.add_sdt_section) as well as EIT sections.
+#[derive(Default)]
+pub struct NvodResolver {
+    reference_services: HashSet<(u16, u16)>,
+    time_shifted_services: HashMap<(u16, u16), u16>,
+    reference_events: HashMap<(u16, u16, u16), Vec<u8>>,
+}
+impl NvodResolver {
+    pub fn new() -> NvodResolver {
+        NvodResolver::default()
+    }
+
+    /// Record the NVOD reference and time-shifted services described in the given SDT section.
+    pub fn add_sdt_section(&mut self, sect: &SdtSection<'_>) {
+        let onid = sect.original_network_id();
+        for service in sect.services() {
+            for desc in service.descriptors::<En300_468Descriptors<'_>>() {
+                match desc {
+                    Ok(En300_468Descriptors::Service(d)) => {
+                        if d.service_type().is_nvod_reference() {
+                            self.reference_services.insert((onid, service.service_id()));
+                        }
+                    }
+                    Ok(En300_468Descriptors::NvodReference(_)) => {
+                        self.reference_services.insert((onid, service.service_id()));
+                    }
+                    Ok(En300_468Descriptors::TimeShiftedService(d)) => {
+                        let reference_service_id = d.reference_service_id();
+                        self.time_shifted_services
+                            .insert((onid, service.service_id()), reference_service_id);
+                        self.reference_services.insert((onid, reference_service_id));
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Record the events of the given EIT section, if `service_id` is known to be an NVOD
+    /// reference service.  Sections for other services are ignored.
+    pub fn add_eit_section(&mut self, service_id: u16, sect: &EitSection<'_>) {
+        let onid = sect.original_network_id();
+        if !self.reference_services.contains(&(onid, service_id)) {
+            return;
+        }
+        for event in sect.events().flatten() {
+            self.reference_events.insert(
+                (onid, service_id, event.event_id()),
+                event.descriptors_data().to_vec(),
+            );
+        }
+    }
+
+    /// The NVOD reference service of the given time-shifted service, if known.
+    pub fn reference_service_id(&self, original_network_id: u16, service_id: u16) -> Option<u16> {
+        self.time_shifted_services
+            .get(&(original_network_id, service_id))
+            .copied()
+    }
+
+    /// Find the reference event described by the given event's [`TimeShiftedEventDescriptor`].
+    ///
+    /// Returns `None` if the event has no such descriptor, or if the reference event has not
+    /// yet been seen.
+    pub fn resolve(
+        &self,
+        original_network_id: u16,
+        event: &Event<'_>,
+    ) -> Option<ReferenceEvent<'_>> {
+        let (service_id, event_id) =
+            event
+                .descriptors::<En300_468Descriptors<'_>>()
+                .find_map(|d| match d {
+                    Ok(En300_468Descriptors::TimeShiftedEvent(d)) => {
+                        Some((d.reference_service_id(), d.reference_event_id()))
+                    }
+                    _ => None,
+                })?;
+        self.reference_events
+            .get(&(original_network_id, service_id, event_id))
+            .map(|data| ReferenceEvent {
+                service_id,
+                event_id,
+                descriptors_data: &data[..],
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nvod_reference_descriptor() {
+        let data = [
+            0x00, 0x01, 0x23, 0x3A, 0x10, 0x01, 0x00, 0x02, 0x23, 0x3A, 0x10, 0x02,
+        ];
+        let desc = NvodReferenceDescriptor::new(0x4B, &data).unwrap();
+        let services: Vec<_> = desc.services().collect();
+        assert_eq!(
+            vec![
+                ServiceTriplet {
+                    original_network_id: 0x233A,
+                    transport_stream_id: 0x0001,
+                    service_id: 0x1001,
+                },
+                ServiceTriplet {
+                    original_network_id: 0x233A,
+                    transport_stream_id: 0x0002,
+                    service_id: 0x1002,
+                },
+            ],
+            services
+        );
+    }
+
+    #[test]
+    fn time_shifted_event_descriptor_too_short() {
+        assert!(matches!(
+            TimeShiftedEventDescriptor::new(0x4F, &[0x10, 0x00, 0x01]),
+            Err(descriptor::DescriptorError::NotEnoughData {
+                tag: 0x4F,
+                actual: 3,
+                expected: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn resolve_time_shifted_event() {
+        #[rustfmt::skip]
+        let sdt = [
+            0x23, 0x3A, // original_network_id
+            0xFF, // reserved
+            // reference service 0x1000
+            0x10, 0x00, 0xFC, 0x80, 0x05,
+            0x48, 0x03, 0x04, 0x00, 0x00, // service_descriptor, service_type=NVOD reference
+            // time-shifted service 0x1001
+            0x10, 0x01, 0xFC, 0x80, 0x04,
+            0x4C, 0x02, 0x10, 0x00, // time_shifted_service_descriptor
+        ];
+        #[rustfmt::skip]
+        let reference_eit = [
+            0x00, 0x01, 0x23, 0x3A, 0x00, 0x50,
+            // event 0x0100, undefined start time
+            0x01, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x30, 0x00, 0x80, 0x0C,
+            0x4D, 0x0A, b'e', b'n', b'g', 0x05, b'F', b'i', b'l', b'm', b'!', 0x00,
+        ];
+        #[rustfmt::skip]
+        let time_shifted_eit = [
+            0x00, 0x01, 0x23, 0x3A, 0x00, 0x4E,
+            // event 0x0200, referencing event 0x0100 of service 0x1000
+            0x02, 0x00, 0xC0, 0x79, 0x12, 0x30, 0x00, 0x01, 0x30, 0x00, 0x80, 0x06,
+            0x4F, 0x04, 0x10, 0x00, 0x01, 0x00,
+        ];
+
+        let mut resolver = NvodResolver::new();
+        resolver.add_sdt_section(&SdtSection::new(&sdt));
+        assert_eq!(Some(0x1000), resolver.reference_service_id(0x233A, 0x1001));
+        assert_eq!(None, resolver.reference_service_id(0x233A, 0x1000));
+
+        // sections for services which are not NVOD reference services are not retained
+        resolver.add_eit_section(0x1001, &EitSection::new(&time_shifted_eit));
+        assert!(resolver.reference_events.is_empty());
+
+        resolver.add_eit_section(0x1000, &EitSection::new(&reference_eit));
+
+        let sect = EitSection::new(&time_shifted_eit);
+        let event = sect.events().next().unwrap().unwrap();
+        let reference = resolver
+            .resolve(sect.original_network_id(), &event)
+            .unwrap();
+        assert_eq!(0x1000, reference.service_id());
+        assert_eq!(0x0100, reference.event_id());
+        let name = reference
+            .descriptors::<En300_468Descriptors<'_>>()
+            .find_map(|d| match d {
+                Ok(En300_468Descriptors::ShortEvent(d)) => Some(d),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!("Film!", name.event_name().unwrap().to_string().unwrap());
+    }
+}
@@ -0,0 +1,199 @@
+//! _PDC Descriptor_ (tag 0x69)
+use crate::time::{self, MjdTimestamp};
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Service codes which may be signalled in place of a real date and time within a
+/// [`ProgrammeIdentificationLabel`], as defined in _ETSI EN 300 231_.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PilServiceCode {
+    /// Timer-control code
+    TimerControl,
+    /// Record inhibit / terminate
+    RecordInhibit,
+    /// Interruption
+    Interruption,
+    /// Continuation
+    Continuation,
+}
+
+/// The _Programme Identification Label_ of _ETSI EN 300 231_: the originally announced start
+/// time of a programme, in the local time of the broadcaster, without a year.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ProgrammeIdentificationLabel {
+    pub day: u8,
+    pub month: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+impl ProgrammeIdentificationLabel {
+    /// The service code signalled by this label, if it does not denote a real date and time
+    pub fn service_code(&self) -> Option<PilServiceCode> {
+        match (self.day, self.month, self.hour, self.minute) {
+            (0, 15, 31, 63) => Some(PilServiceCode::TimerControl),
+            (0, 15, 30, 63) => Some(PilServiceCode::RecordInhibit),
+            (0, 15, 29, 63) => Some(PilServiceCode::Interruption),
+            (0, 15, 28, 63) => Some(PilServiceCode::Continuation),
+            _ => None,
+        }
+    }
+
+    fn is_valid_time(&self) -> bool {
+        (1..=31).contains(&self.day)
+            && (1..=12).contains(&self.month)
+            && self.hour < 24
+            && self.minute < 60
+    }
+
+    /// Resolve this label into an absolute UTC time (in seconds since the Unix epoch), using the
+    /// start time of the event carrying it to determine the year.
+    ///
+    /// `local_time_offset` is the offset of the broadcaster's local time from UTC, in minutes, for
+    /// example as given by
+    /// [`LocalTimeOffsetDescriptor::offset_at()`](../local_time_offset/struct.LocalTimeOffsetDescriptor.html#method.offset_at)
+    /// for the event's start time.
+    ///
+    /// Returns `None` for service codes and other labels not denoting a valid date and time.
+    pub fn to_unix_timestamp(
+        &self,
+        event_start: &MjdTimestamp<'_>,
+        local_time_offset: i32,
+    ) -> Option<i64> {
+        if !self.is_valid_time() {
+            return None;
+        }
+        let offset = i64::from(local_time_offset) * 60;
+        let local_start = event_start.to_unix_timestamp() + offset;
+        let (year, _, _) = time::date_from_mjd(local_start.div_euclid(86400) + 40587);
+        // the label may refer to a date in the adjacent year when an event spans new year
+        (year - 1..=year + 1)
+            .map(|y| {
+                let days =
+                    time::mjd_from_date(y, i32::from(self.month), i32::from(self.day)) - 40587;
+                days * 86400 + i64::from(self.hour) * 3600 + i64::from(self.minute) * 60
+            })
+            .min_by_key(|local| (local - local_start).abs())
+            .map(|local| local - offset)
+    }
+}
+
+/// Gives the _Programme Identification Label_ used by video recorders to follow changes to the
+/// broadcast time of an event, as defined in _ETSI EN 300 468_ section 6.2.30.
+pub struct PdcDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> PdcDescriptor<'buf> {
+    pub const TAG: u8 = 0x69;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<PdcDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 3 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 3,
+            });
+        }
+        Ok(PdcDescriptor { data })
+    }
+
+    pub fn programme_identification_label(&self) -> ProgrammeIdentificationLabel {
+        let pil = u32::from(self.data[0] & 0b1111) << 16
+            | u32::from(self.data[1]) << 8
+            | u32::from(self.data[2]);
+        ProgrammeIdentificationLabel {
+            day: (pil >> 15) as u8,
+            month: (pil >> 11) as u8 & 0b1111,
+            hour: (pil >> 6) as u8 & 0b1_1111,
+            minute: pil as u8 & 0b11_1111,
+        }
+    }
+}
+impl<'buf> fmt::Debug for PdcDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("PdcDescriptor")
+            .field(
+                "programme_identification_label",
+                &self.programme_identification_label(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pil_bytes(day: u32, month: u32, hour: u32, minute: u32) -> [u8; 3] {
+        let pil = 0xF0_0000 | day << 15 | month << 11 | hour << 6 | minute;
+        [(pil >> 16) as u8, (pil >> 8) as u8, pil as u8]
+    }
+
+    #[test]
+    fn pdc_descriptor() {
+        let data = pil_bytes(24, 12, 20, 15);
+        let desc = PdcDescriptor::new(0x69, &data).unwrap();
+        let pil = desc.programme_identification_label();
+        assert_eq!(
+            ProgrammeIdentificationLabel {
+                day: 24,
+                month: 12,
+                hour: 20,
+                minute: 15
+            },
+            pil
+        );
+        assert_eq!(None, pil.service_code());
+
+        let data = pil_bytes(0, 15, 29, 63);
+        let pil = PdcDescriptor::new(0x69, &data)
+            .unwrap()
+            .programme_identification_label();
+        assert_eq!(Some(PilServiceCode::Interruption), pil.service_code());
+        assert!(PdcDescriptor::new(0x69, &data[..2]).is_err());
+    }
+
+    #[test]
+    fn pil_to_utc() {
+        // event starts 2024-03-31 19:17:00 UTC (21:17 CEST), announced for 21:15 local time
+        let start = [0xEB, 0xF0, 0x19, 0x17, 0x00];
+        let start = MjdTimestamp::new(&start).unwrap();
+        let pil = ProgrammeIdentificationLabel {
+            day: 31,
+            month: 3,
+            hour: 21,
+            minute: 15,
+        };
+        assert_eq!(
+            Some(start.to_unix_timestamp() - 120),
+            pil.to_unix_timestamp(&start, 120)
+        );
+
+        // event starts 2024-01-01 00:05 local time (2023-12-31 23:05 UTC), announced for
+        // 31st December at 23:55 local time
+        let start = [0xEB, 0x95, 0x23, 0x05, 0x00];
+        let start = MjdTimestamp::new(&start).unwrap();
+        assert_eq!((2023, 12, 31), start.date());
+        let pil = ProgrammeIdentificationLabel {
+            day: 31,
+            month: 12,
+            hour: 23,
+            minute: 55,
+        };
+        assert_eq!(
+            Some(start.to_unix_timestamp() - 600),
+            pil.to_unix_timestamp(&start, 60)
+        );
+
+        let pil = ProgrammeIdentificationLabel {
+            day: 0,
+            month: 15,
+            hour: 31,
+            minute: 63,
+        };
+        assert_eq!(None, pil.to_unix_timestamp(&start, 60));
+    }
+}
@@ -0,0 +1,346 @@
+//! _Private Data Specifier Descriptor_ (tag 0x5F), and decoding of the privately defined
+//! descriptors (tags 0x80 to 0xFE) whose meaning depends on it.
+//!
+//! The same private tag value can mean quite different things depending on which organisation
+//! defined it; for example tag 0x83 is a _logical_channel_descriptor_ for EACEM, NorDig and UK
+//! DTT, but each uses a different layout.  A private descriptor can only be interpreted using the
+//! `private_data_specifier` given by the most recent preceding
+//! [`PrivateDataSpecifierDescriptor`] in the same descriptor loop, so rather than iterating a
+//! loop with `DescriptorIter`, use [`PrivateDescriptorIter`] to have this tracked automatically.
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+use std::marker;
+
+/// `private_data_specifier` registered for EACEM / EICTA
+pub const EACEM: u32 = 0x0000_0028;
+/// `private_data_specifier` registered for NorDig
+pub const NORDIG: u32 = 0x0000_0029;
+/// `private_data_specifier` registered for the UK Digital Terrestrial Television group (DTG)
+pub const UK_DTT: u32 = 0x0000_233A;
+
+/// Identifies the organisation defining any following private descriptors, as defined in
+/// _ETSI EN 300 468_ section 6.2.31.
+pub struct PrivateDataSpecifierDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> PrivateDataSpecifierDescriptor<'buf> {
+    pub const TAG: u8 = 0x5F;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<PrivateDataSpecifierDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 4 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 4,
+            });
+        }
+        Ok(PrivateDataSpecifierDescriptor { data })
+    }
+
+    /// Value registered with the DVB Project (see _ETSI TS 101 162_)
+    pub fn private_data_specifier(&self) -> u32 {
+        u32::from(self.data[0]) << 24
+            | u32::from(self.data[1]) << 16
+            | u32::from(self.data[2]) << 8
+            | u32::from(self.data[3])
+    }
+}
+impl<'buf> fmt::Debug for PrivateDataSpecifierDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("PrivateDataSpecifierDescriptor")
+            .field(
+                "private_data_specifier",
+                &format!("{:#010x}", self.private_data_specifier()),
+            )
+            .finish()
+    }
+}
+
+/// Implemented by types that can decode the descriptors with tags in the range 0x80 to 0xFE,
+/// given the `private_data_specifier` in effect (if any).
+///
+/// [`PrivateDescriptors`] is the implementation provided by this crate, but applications may
+/// supply their own in order to handle descriptors which this crate does not know about.
+pub trait PrivateDescriptor<'buf>: Sized {
+    /// Decode the descriptor in `buf` (including the two-byte tag and length header).
+    fn from_bytes(
+        private_data_specifier: Option<u32>,
+        buf: &'buf [u8],
+    ) -> Result<Self, descriptor::DescriptorError>;
+}
+
+/// A descriptor produced by [`PrivateDescriptorIter`]
+#[derive(Debug)]
+pub enum LoopDescriptor<Desc, Priv> {
+    /// A descriptor with a tag outside of the private range, decoded as normal
+    Public(Desc),
+    /// A descriptor with a tag in the range 0x80 to 0xFE, decoded according to the
+    /// `private_data_specifier` in effect
+    Private(Priv),
+}
+
+/// Iterates over a descriptor loop, keeping track of the `private_data_specifier` declared by
+/// any [`PrivateDataSpecifierDescriptor`] so that private descriptors can be decoded correctly.
+///
+/// Descriptors with tags in the range 0x80 to 0xFE are decoded using the `Priv` type, and all
+/// others using the `Desc` type.
+pub struct PrivateDescriptorIter<'buf, Desc, Priv> {
+    buf: &'buf [u8],
+    private_data_specifier: Option<u32>,
+    phantom: marker::PhantomData<(Desc, Priv)>,
+}
+impl<'buf, Desc, Priv> PrivateDescriptorIter<'buf, Desc, Priv>
+where
+    Desc: descriptor::Descriptor<'buf>,
+    Priv: PrivateDescriptor<'buf>,
+{
+    pub fn new(buf: &'buf [u8]) -> PrivateDescriptorIter<'buf, Desc, Priv> {
+        PrivateDescriptorIter {
+            buf,
+            private_data_specifier: None,
+            phantom: marker::PhantomData,
+        }
+    }
+
+    /// The `private_data_specifier` that will be applied to the next private descriptor.
+    pub fn private_data_specifier(&self) -> Option<u32> {
+        self.private_data_specifier
+    }
+}
+impl<'buf, Desc, Priv> Iterator for PrivateDescriptorIter<'buf, Desc, Priv>
+where
+    Desc: descriptor::Descriptor<'buf>,
+    Priv: PrivateDescriptor<'buf>,
+{
+    type Item = Result<LoopDescriptor<Desc, Priv>, descriptor::DescriptorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        if self.buf.len() < 2 {
+            let buflen = self.buf.len();
+            self.buf = &self.buf[0..0];
+            return Some(Err(descriptor::DescriptorError::BufferTooShort { buflen }));
+        }
+        let tag = self.buf[0];
+        let len = usize::from(self.buf[1]);
+        if len + 2 > self.buf.len() {
+            let remaining_len = self.buf.len();
+            self.buf = &self.buf[0..0];
+            return Some(Err(descriptor::DescriptorError::TagTooLongForBuffer {
+                taglen: len,
+                remaining_len,
+            }));
+        }
+        let (desc, rest) = self.buf.split_at(len + 2);
+        self.buf = rest;
+        match tag {
+            0x80..=0xFE => Some(
+                Priv::from_bytes(self.private_data_specifier, desc).map(LoopDescriptor::Private),
+            ),
+            _ => {
+                if tag == PrivateDataSpecifierDescriptor::TAG {
+                    // a malformed specifier leaves us unable to interpret what follows
+                    self.private_data_specifier =
+                        PrivateDataSpecifierDescriptor::new(tag, &desc[2..])
+                            .ok()
+                            .map(|d| d.private_data_specifier());
+                }
+                Some(Desc::from_bytes(desc).map(LoopDescriptor::Public))
+            }
+        }
+    }
+}
+
+/// One entry from a [`LogicalChannelDescriptor`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LogicalChannel {
+    pub service_id: u16,
+    /// If `false`, the service should not normally be listed to the viewer (but may still be
+    /// selected by entering its channel number).
+    pub visible_service_flag: bool,
+    pub logical_channel_number: u16,
+}
+
+/// Assigns channel numbers to services, as defined by EACEM / EICTA, NorDig (version 1) and the
+/// UK DTT _D-Book_, all using private tag 0x83.
+///
+/// NorDig allows 14-bit channel numbers, whereas the other definitions allow only 10 bits.
+pub struct LogicalChannelDescriptor<'buf> {
+    private_data_specifier: u32,
+    data: &'buf [u8],
+}
+impl<'buf> LogicalChannelDescriptor<'buf> {
+    pub const TAG: u8 = 0x83;
+
+    pub fn new(
+        private_data_specifier: u32,
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<LogicalChannelDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(LogicalChannelDescriptor {
+            private_data_specifier,
+            data,
+        })
+    }
+
+    pub fn private_data_specifier(&self) -> u32 {
+        self.private_data_specifier
+    }
+
+    pub fn channels(&self) -> impl Iterator<Item = LogicalChannel> + 'buf {
+        let lcn_mask = if self.private_data_specifier == NORDIG {
+            0b0011_1111_1111_1111
+        } else {
+            0b0000_0011_1111_1111
+        };
+        self.data.chunks_exact(4).map(move |d| LogicalChannel {
+            service_id: u16::from(d[0]) << 8 | u16::from(d[1]),
+            visible_service_flag: d[2] & 0b1000_0000 != 0,
+            logical_channel_number: (u16::from(d[2]) << 8 | u16::from(d[3])) & lcn_mask,
+        })
+    }
+}
+impl<'buf> fmt::Debug for LogicalChannelDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("LogicalChannelDescriptor")
+            .field(
+                "private_data_specifier",
+                &format!("{:#010x}", self.private_data_specifier),
+            )
+            .field("channels", &self.channels().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A private descriptor for which no decoder is known, given the `private_data_specifier` in
+/// effect
+#[derive(Debug)]
+pub struct UnknownPrivateDescriptor<'buf> {
+    pub private_data_specifier: Option<u32>,
+    pub descriptor: descriptor::UnknownDescriptor<'buf>,
+}
+
+/// The private descriptors supported by this crate.
+#[derive(Debug)]
+pub enum PrivateDescriptors<'buf> {
+    /// Tag 0x83 under the EACEM, NorDig or UK DTT private data specifiers
+    LogicalChannel(LogicalChannelDescriptor<'buf>),
+    Unknown(UnknownPrivateDescriptor<'buf>),
+}
+impl<'buf> PrivateDescriptor<'buf> for PrivateDescriptors<'buf> {
+    fn from_bytes(
+        private_data_specifier: Option<u32>,
+        buf: &'buf [u8],
+    ) -> Result<Self, descriptor::DescriptorError> {
+        if buf.len() < 2 {
+            return Err(descriptor::DescriptorError::BufferTooShort { buflen: buf.len() });
+        }
+        let tag = buf[0];
+        let len = usize::from(buf[1]);
+        let tag_end = len + 2;
+        if tag_end > buf.len() {
+            return Err(descriptor::DescriptorError::TagTooLongForBuffer {
+                taglen: len,
+                remaining_len: buf.len(),
+            });
+        }
+        let payload = &buf[2..tag_end];
+        match (private_data_specifier, tag) {
+            (Some(pds @ (EACEM | NORDIG | UK_DTT)), LogicalChannelDescriptor::TAG) => {
+                Ok(PrivateDescriptors::LogicalChannel(
+                    LogicalChannelDescriptor::new(pds, tag, payload)?,
+                ))
+            }
+            _ => Ok(PrivateDescriptors::Unknown(UnknownPrivateDescriptor {
+                private_data_specifier,
+                descriptor: descriptor::UnknownDescriptor::new(tag, payload)?,
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::En300_468Descriptors;
+
+    #[rustfmt::skip]
+    const LCN: [u8; 6] = [
+        0x83, 0x04,
+        0x10, 0x44, 0b1111_1100, 0x65, // service_id=0x1044, visible, lcn bits=0x3c65
+    ];
+
+    fn loop_with(specifier: u32) -> Vec<u8> {
+        let mut data = vec![0x5F, 0x04];
+        data.extend_from_slice(&specifier.to_be_bytes());
+        data.extend_from_slice(&LCN);
+        data
+    }
+
+    fn channels(data: &[u8]) -> Vec<LogicalChannel> {
+        PrivateDescriptorIter::<En300_468Descriptors<'_>, PrivateDescriptors<'_>>::new(data)
+            .filter_map(|d| match d {
+                Ok(LoopDescriptor::Private(PrivateDescriptors::LogicalChannel(d))) => Some(d),
+                _ => None,
+            })
+            .flat_map(|d| d.channels())
+            .collect()
+    }
+
+    #[test]
+    fn private_data_specifier_descriptor() {
+        let desc = PrivateDataSpecifierDescriptor::new(0x5F, &[0x00, 0x00, 0x23, 0x3A]).unwrap();
+        assert_eq!(UK_DTT, desc.private_data_specifier());
+        assert!(PrivateDataSpecifierDescriptor::new(0x5F, &[0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn specifier_selects_decoder() {
+        let expected = |logical_channel_number| {
+            vec![LogicalChannel {
+                service_id: 0x1044,
+                visible_service_flag: true,
+                logical_channel_number,
+            }]
+        };
+        assert_eq!(expected(0x065), channels(&loop_with(UK_DTT)));
+        assert_eq!(expected(0x065), channels(&loop_with(EACEM)));
+        assert_eq!(expected(0x3c65), channels(&loop_with(NORDIG)));
+        assert!(channels(&loop_with(0x0000_0002)).is_empty());
+        // no private_data_specifier_descriptor precedes the private descriptor
+        assert!(channels(&LCN).is_empty());
+    }
+
+    #[test]
+    fn specifier_is_tracked() {
+        let mut data = loop_with(NORDIG);
+        data.extend_from_slice(&loop_with(EACEM));
+        let mut iter =
+            PrivateDescriptorIter::<En300_468Descriptors<'_>, PrivateDescriptors<'_>>::new(&data);
+        assert_eq!(None, iter.private_data_specifier());
+        assert!(matches!(
+            iter.next(),
+            Some(Ok(LoopDescriptor::Public(
+                En300_468Descriptors::PrivateDataSpecifier(_)
+            )))
+        ));
+        assert_eq!(Some(NORDIG), iter.private_data_specifier());
+        iter.next();
+        iter.next();
+        assert_eq!(Some(EACEM), iter.private_data_specifier());
+        match iter.next() {
+            Some(Ok(LoopDescriptor::Private(PrivateDescriptors::LogicalChannel(d)))) => {
+                assert_eq!(EACEM, d.private_data_specifier())
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(iter.next().is_none());
+    }
+}
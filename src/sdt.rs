@@ -1,13 +1,17 @@
 //! _Service Description Table_ section data
+use crate::country_availability;
+use crate::multilingual::{language_matches, MultilingualServiceName};
+use crate::private_data::{PrivateDescriptor, PrivateDescriptorIter};
+use crate::service_availability;
 use crate::ActualOther;
-use crate::Text;
+use crate::{Text, TextError};
 use mpeg2ts_reader::{demultiplex, descriptor, packet, psi};
 use std::fmt;
 use std::marker;
 
 pub const SDT_PID: packet::Pid = packet::Pid::new(0x11);
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ServiceType {
     Reserved(u8),
     DigitalTelevision,
@@ -74,6 +78,30 @@ impl ServiceType {
             0xff => ServiceType::Reserved(id),
         }
     }
+
+    /// `true` for the NVOD reference service types, whose events are described once and then
+    /// broadcast at staggered times by one or more time-shifted services.
+    pub fn is_nvod_reference(&self) -> bool {
+        matches!(
+            self,
+            ServiceType::NvodReference
+                | ServiceType::H264AvcSdNvodReference
+                | ServiceType::H264AvcHdNvodReference
+                | ServiceType::H264AvcFrameCompatiblePlanoStereoscopicHdNvodReference
+        )
+    }
+
+    /// `true` for the NVOD time-shifted service types, whose events refer back to the events of
+    /// an NVOD reference service.
+    pub fn is_nvod_time_shifted(&self) -> bool {
+        matches!(
+            self,
+            ServiceType::NvodTimeShifted
+                | ServiceType::H264AvcSdNvodTimeShifted
+                | ServiceType::H264AvcHdNvodTimeShifted
+                | ServiceType::H264AvcFrameCompatiblePlanoStereoscopicHdNvodTimeShifted
+        )
+    }
 }
 
 pub struct ServiceDescriptor<'buf> {
@@ -175,6 +203,112 @@ impl<'buf> Service<'buf> {
         let end = start + self.descriptors_loop_length();
         descriptor::DescriptorIter::new(&self.data[start..end])
     }
+    /// Like [`descriptors()`](#method.descriptors), but decoding any private descriptors
+    /// according to the `private_data_specifier` in effect.  See the
+    /// [`private_data`](../private_data/index.html) module.
+    pub fn descriptors_with_private<Desc, Priv>(&self) -> PrivateDescriptorIter<'buf, Desc, Priv>
+    where
+        Desc: descriptor::Descriptor<'buf>,
+        Priv: PrivateDescriptor<'buf>,
+    {
+        let start = 5;
+        let end = start + self.descriptors_loop_length();
+        PrivateDescriptorIter::new(&self.data[start..end])
+    }
+    /// The `CA_system_id` values listed by any
+    /// [`CaIdentifierDescriptor`](../ca/struct.CaIdentifierDescriptor.html) of this service,
+    /// identifying the conditional access systems used when `free_ca_mode` is set.
+    pub fn ca_system_ids(&self) -> impl Iterator<Item = u16> + 'buf {
+        self.descriptors::<super::En300_468Descriptors<'buf>>()
+            .filter_map(|d| match d {
+                Ok(super::En300_468Descriptors::CaIdentifier(d)) => Some(d),
+                _ => None,
+            })
+            .flat_map(|d| d.ca_system_ids())
+    }
+    /// Evaluates this service's
+    /// [`CountryAvailabilityDescriptor`](../country_availability/struct.CountryAvailabilityDescriptor.html)
+    /// instances using [`country_availability::is_available()`](../country_availability/fn.is_available.html).
+    pub fn is_available_in_country(&self, country_code: &str) -> bool {
+        let descs: Vec<_> = self
+            .descriptors::<super::En300_468Descriptors<'buf>>()
+            .filter_map(|d| match d {
+                Ok(super::En300_468Descriptors::CountryAvailability(d)) => Some(d),
+                _ => None,
+            })
+            .collect();
+        country_availability::is_available(&descs, country_code)
+    }
+    /// Evaluates this service's
+    /// [`ServiceAvailabilityDescriptor`](../service_availability/struct.ServiceAvailabilityDescriptor.html)
+    /// instances using [`service_availability::is_available()`](../service_availability/fn.is_available.html),
+    /// to decide if the service is intended for reception in the terrestrial cell with the given
+    /// `cell_id`, as found in the T2 delivery system descriptor or cell list descriptor of the NIT.
+    pub fn is_available_in_cell(&self, cell_id: u16) -> bool {
+        let descs: Vec<_> = self
+            .descriptors::<super::En300_468Descriptors<'buf>>()
+            .filter_map(|d| match d {
+                Ok(super::En300_468Descriptors::ServiceAvailability(d)) => Some(d),
+                _ => None,
+            })
+            .collect();
+        service_availability::is_available(&descs, cell_id)
+    }
+    fn localised_name(
+        &self,
+        preferred_languages: &[&str],
+        multilingual: impl Fn(&MultilingualServiceName<'buf>) -> Result<Text<'buf>, TextError>,
+        fallback: impl Fn(&ServiceDescriptor<'buf>) -> Result<Text<'buf>, TextError>,
+    ) -> Option<Text<'buf>> {
+        let names: Vec<_> = self
+            .descriptors::<super::En300_468Descriptors<'buf>>()
+            .filter_map(|d| match d {
+                Ok(super::En300_468Descriptors::MultilingualServiceName(d)) => Some(d),
+                _ => None,
+            })
+            .flat_map(|d| d.names())
+            .collect();
+        preferred_languages
+            .iter()
+            .find_map(|lang| {
+                names
+                    .iter()
+                    .filter(|n| language_matches(n.language_code(), lang))
+                    .filter_map(|n| multilingual(n).ok())
+                    // an empty name gives nothing to present, so try the next language instead
+                    .find(|t| t.to_string().map_or(true, |s| !s.is_empty()))
+            })
+            .or_else(|| {
+                self.descriptors::<super::En300_468Descriptors<'buf>>()
+                    .find_map(|d| match d {
+                        Ok(super::En300_468Descriptors::Service(d)) => fallback(&d).ok(),
+                        _ => None,
+                    })
+            })
+    }
+    /// The service name in the first of the given ISO 639-2 languages for which a
+    /// [`MultilingualServiceNameDescriptor`](../multilingual/struct.MultilingualServiceNameDescriptor.html)
+    /// provides a non-empty one, falling back to the name given by the [`ServiceDescriptor`].
+    pub fn localised_service_name(&self, preferred_languages: &[&str]) -> Option<Text<'buf>> {
+        self.localised_name(
+            preferred_languages,
+            |n| n.service_name(),
+            |d| d.service_name(),
+        )
+    }
+    /// The service provider name in the first of the given ISO 639-2 languages for which a
+    /// [`MultilingualServiceNameDescriptor`](../multilingual/struct.MultilingualServiceNameDescriptor.html)
+    /// provides a non-empty one, falling back to the name given by the [`ServiceDescriptor`].
+    pub fn localised_service_provider_name(
+        &self,
+        preferred_languages: &[&str],
+    ) -> Option<Text<'buf>> {
+        self.localised_name(
+            preferred_languages,
+            |n| n.service_provider_name(),
+            |d| d.service_provider_name(),
+        )
+    }
 }
 struct DescriptorsDebug<'buf, Desc: descriptor::Descriptor<'buf>>(
     &'buf Service<'buf>,
@@ -388,6 +522,43 @@ mod test {
         }
     }
 
+    #[test]
+    fn localised_service_name() {
+        #[rustfmt::skip]
+        let data = [
+            0x23, 0x3A, 0xFF,
+            0x10, 0x01, 0xFC, 0x80, 0x1D,
+            0x48, 0x09, 0x01, 0x03, b'S', b'R', b'F', 0x03, b'S', b'F', b'1',
+            0x5D, 0x10,
+            b'f', b'r', b'a', 0x03, b'R', b'T', b'S', 0x03, b'R', b'T', b'S',
+            b'i', b't', b'a', 0x00, 0x00,
+        ];
+        let sect = SdtSection::new(&data);
+        let service = sect.services().next().unwrap();
+        let name = |langs: &[&str]| {
+            service
+                .localised_service_name(langs)
+                .unwrap()
+                .to_string()
+                .unwrap()
+                .into_owned()
+        };
+        assert_eq!("RTS", name(&["fra", "deu"]));
+        assert_eq!("RTS", name(&["roh", "FRA"]));
+        assert_eq!("SF1", name(&["ita"]));
+        assert_eq!("RTS", name(&["ita", "fra"]));
+        assert_eq!("SF1", name(&["deu"]));
+        assert_eq!("SF1", name(&[]));
+        assert_eq!(
+            "SRF",
+            service
+                .localised_service_provider_name(&["deu"])
+                .unwrap()
+                .to_string()
+                .unwrap()
+        );
+    }
+
     struct AssertConsumer;
     impl SdtConsumer for AssertConsumer {
         fn consume(&mut self, sdt: ActualOther<&SdtSection<'_>>) {
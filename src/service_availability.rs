@@ -0,0 +1,105 @@
+//! _Service Availability Descriptor_ (tag 0x72)
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// Lists the terrestrial cells in which a service is (or is not) intended to be received, as
+/// defined in _ETSI EN 300 468_ section 6.2.34.
+///
+/// The `cell_id` values correspond to those of the cell list and terrestrial delivery system
+/// descriptors of the NIT.
+pub struct ServiceAvailabilityDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ServiceAvailabilityDescriptor<'buf> {
+    pub const TAG: u8 = 0x72;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<ServiceAvailabilityDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.is_empty() {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: 0,
+                expected: 1,
+            });
+        }
+        Ok(ServiceAvailabilityDescriptor { data })
+    }
+
+    /// If `true`, the service is available in the listed cells; if `false`, it is available
+    /// everywhere except the listed cells.
+    pub fn availability_flag(&self) -> bool {
+        self.data[0] & 0b1000_0000 != 0
+    }
+
+    pub fn cell_ids(&self) -> impl Iterator<Item = u16> + 'buf {
+        self.data[1..]
+            .chunks_exact(2)
+            .map(|c| u16::from(c[0]) << 8 | u16::from(c[1]))
+    }
+
+    fn lists(&self, cell_id: u16) -> bool {
+        self.cell_ids().any(|id| id == cell_id)
+    }
+}
+impl<'buf> fmt::Debug for ServiceAvailabilityDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ServiceAvailabilityDescriptor")
+            .field("availability_flag", &self.availability_flag())
+            .field("cell_ids", &self.cell_ids().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Evaluates all the [`ServiceAvailabilityDescriptor`] instances from a single descriptor loop
+/// to decide if the service is intended for reception in the cell with the given `cell_id`.
+///
+/// This crate does not yet decode the NIT, so the caller must determine the receiver's
+/// `cell_id`: it is the `cell_id` of the NIT's T2 delivery system descriptor (DVB-T2) or cell
+/// list descriptor (DVB-T) entry for the frequency that the receiver is tuned to.
+///
+/// - A cell listed by any descriptor with `availability_flag` unset is excluded.
+/// - Otherwise, if any descriptor has `availability_flag` set, the cell must be listed by one of
+///   those descriptors.
+/// - Otherwise (including when there are no descriptors at all) the service is available.
+pub fn is_available<'a, 'buf: 'a>(
+    descriptors: impl IntoIterator<Item = &'a ServiceAvailabilityDescriptor<'buf>>,
+    cell_id: u16,
+) -> bool {
+    crate::is_included(
+        descriptors
+            .into_iter()
+            .map(|desc| (desc.availability_flag(), desc.lists(cell_id))),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn service_availability_descriptor() {
+        let data = [0xFF, 0x00, 0x01, 0x12, 0x34];
+        let desc = ServiceAvailabilityDescriptor::new(0x72, &data).unwrap();
+        assert!(desc.availability_flag());
+        assert_eq!(vec![0x0001, 0x1234], desc.cell_ids().collect::<Vec<_>>());
+        assert!(ServiceAvailabilityDescriptor::new(0x72, &[]).is_err());
+    }
+
+    #[test]
+    fn availability() {
+        let include = [0x80, 0x00, 0x01, 0x00, 0x02];
+        let include = ServiceAvailabilityDescriptor::new(0x72, &include).unwrap();
+        let exclude = [0x7F, 0x00, 0x02];
+        let exclude = ServiceAvailabilityDescriptor::new(0x72, &exclude).unwrap();
+
+        assert!(is_available(std::iter::empty(), 3));
+        assert!(is_available([&include], 1));
+        assert!(!is_available([&include], 3));
+        assert!(!is_available([&include, &exclude], 2));
+        assert!(is_available([&exclude], 3));
+        assert!(!is_available([&exclude], 2));
+    }
+}
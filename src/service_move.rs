@@ -0,0 +1,224 @@
+//! _Service Move Descriptor_ (tag 0x60) and _Service Relocated Descriptor_ (extension tag 0x0B)
+//!
+//! When a broadcaster moves a service to a different transport stream (or changes its
+//! `service_id`), these descriptors allow a receiver to carry over state associated with the
+//! service, such as favourites lists or scheduled recordings.  See [`ServiceMoves`].
+use crate::extension::ExtensionDescriptors;
+use crate::sdt::SdtSection;
+use crate::{En300_468Descriptors, ServiceTriplet};
+use mpeg2ts_reader::descriptor;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Placed in the PMT of a service which is about to move, to identify the service in its new
+/// location, as defined in _ETSI EN 300 468_ section 6.2.36.
+pub struct ServiceMoveDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ServiceMoveDescriptor<'buf> {
+    pub const TAG: u8 = 0x60;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<ServiceMoveDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        if data.len() < 6 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag,
+                actual: data.len(),
+                expected: 6,
+            });
+        }
+        Ok(ServiceMoveDescriptor { data })
+    }
+
+    /// The new location of the service
+    pub fn new_service(&self) -> ServiceTriplet {
+        ServiceTriplet::from_bytes(self.data)
+    }
+}
+impl<'buf> fmt::Debug for ServiceMoveDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ServiceMoveDescriptor")
+            .field("new_service", &self.new_service())
+            .finish()
+    }
+}
+
+/// Placed in the SDT entry of a service which has moved, to identify the service's previous
+/// location, as defined in _ETSI EN 300 468_ section 6.4.10.
+///
+/// This is carried within an
+/// [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct ServiceRelocatedDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> ServiceRelocatedDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x0B;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<ServiceRelocatedDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        if data.len() < 6 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected: 6,
+            });
+        }
+        Ok(ServiceRelocatedDescriptor { data })
+    }
+
+    /// The previous location of the service
+    pub fn old_service(&self) -> ServiceTriplet {
+        ServiceTriplet::from_bytes(self.data)
+    }
+}
+impl<'buf> fmt::Debug for ServiceRelocatedDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ServiceRelocatedDescriptor")
+            .field("old_service", &self.old_service())
+            .finish()
+    }
+}
+
+/// Records the services which have moved, so that references to a service's old location can be
+/// updated to its current one.
+///
+/// Moves are learned from the service relocated descriptors of SDT sections passed to
+/// [`add_sdt_section()`](#method.add_sdt_section), or may be given directly with
+/// [`add_move()`](#method.add_move) (for example from a `ServiceMoveDescriptor` found in a PMT).
+#[derive(Default)]
+pub struct ServiceMoves {
+    moves: HashMap<ServiceTriplet, ServiceTriplet>,
+}
+impl ServiceMoves {
+    pub fn new() -> ServiceMoves {
+        ServiceMoves::default()
+    }
+
+    /// Record that the service previously at `old` can now be found at `new`, replacing any move
+    /// previously recorded for `old`.
+    pub fn add_move(&mut self, old: ServiceTriplet, new: ServiceTriplet) {
+        if old != new {
+            self.moves.insert(old, new);
+        }
+    }
+
+    /// Record the moves described by the service relocated descriptors of the given SDT section.
+    ///
+    /// The `transport_stream_id` of the section is not part of the `SdtSection` data, so must be
+    /// supplied from the section's table syntax header (`psi::TableSyntaxHeader::id()`).
+    pub fn add_sdt_section(&mut self, transport_stream_id: u16, sect: &SdtSection<'_>) {
+        for service in sect.services() {
+            let new = ServiceTriplet {
+                original_network_id: sect.original_network_id(),
+                transport_stream_id,
+                service_id: service.service_id(),
+            };
+            for desc in service.descriptors::<En300_468Descriptors<'_>>().flatten() {
+                if let En300_468Descriptors::Extension(ext) = desc {
+                    if let Ok(ExtensionDescriptors::ServiceRelocated(d)) = ext.descriptor() {
+                        self.add_move(d.old_service(), new);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The service to which the service at `old` has moved, if a move has been recorded
+    pub fn moved_to(&self, old: &ServiceTriplet) -> Option<ServiceTriplet> {
+        self.moves.get(old).copied()
+    }
+
+    /// The current location of the given service, following any number of recorded moves.  If the
+    /// service has not moved, it is returned unchanged.
+    pub fn resolve(&self, service: ServiceTriplet) -> ServiceTriplet {
+        let mut current = service;
+        // bound the number of steps, in case moves back and forth have created a cycle
+        for _ in 0..self.moves.len() {
+            match self.moves.get(&current) {
+                Some(next) => current = *next,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// All recorded moves, as `(old, new)` pairs
+    pub fn moves(&self) -> impl Iterator<Item = (&ServiceTriplet, &ServiceTriplet)> {
+        self.moves.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn service_move_descriptor() {
+        let data = [0x00, 0x01, 0x00, 0x02, 0x10, 0x01];
+        let desc = ServiceMoveDescriptor::new(0x60, &data).unwrap();
+        assert_eq!(
+            ServiceTriplet {
+                original_network_id: 1,
+                transport_stream_id: 2,
+                service_id: 0x1001,
+            },
+            desc.new_service()
+        );
+        assert!(ServiceMoveDescriptor::new(0x60, &data[..5]).is_err());
+    }
+
+    #[test]
+    fn service_moves() {
+        #[rustfmt::skip]
+        let sdt = [
+            0x23, 0x3A, 0xFF,
+            // service_id 0x2001, previously 0x233A/0x1000/0x1001
+            0x20, 0x01, 0xFC, 0x80, 0x09,
+            0x7F, 0x07, 0x0B, 0x23, 0x3A, 0x10, 0x00, 0x10, 0x01,
+            // service_id 0x2002, no move
+            0x20, 0x02, 0xFC, 0x80, 0x00,
+        ];
+        let sect = SdtSection::new(&sdt);
+        let mut moves = ServiceMoves::new();
+        moves.add_sdt_section(0x2000, &sect);
+
+        let triplet = |tsid, sid| ServiceTriplet {
+            original_network_id: 0x233A,
+            transport_stream_id: tsid,
+            service_id: sid,
+        };
+        assert_eq!(
+            Some(triplet(0x2000, 0x2001)),
+            moves.moved_to(&triplet(0x1000, 0x1001))
+        );
+        assert_eq!(None, moves.moved_to(&triplet(0x2000, 0x2002)));
+
+        moves.add_move(triplet(0x2000, 0x2001), triplet(0x3000, 0x3001));
+        assert_eq!(
+            triplet(0x3000, 0x3001),
+            moves.resolve(triplet(0x1000, 0x1001))
+        );
+        assert_eq!(
+            triplet(0x2000, 0x2002),
+            moves.resolve(triplet(0x2000, 0x2002))
+        );
+
+        // a service moving back to its original location must not cause resolve() to loop; after
+        // following as many moves as have been recorded, it ends up back where it started
+        moves.add_move(triplet(0x3000, 0x3001), triplet(0x1000, 0x1001));
+        assert_eq!(
+            triplet(0x1000, 0x1001),
+            moves.resolve(triplet(0x1000, 0x1001))
+        );
+        assert_eq!(
+            triplet(0x2000, 0x2001),
+            moves.resolve(triplet(0x2000, 0x2001))
+        );
+    }
+}
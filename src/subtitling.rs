@@ -0,0 +1,484 @@
+//! _Subtitling Descriptor_ (tag 0x59) and _TTML Subtitling Descriptor_ (extension tag 0x20)
+use crate::{Text, TextError};
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// The kind of subtitles in a [`SubtitlingEntry`], taken from the `component_type` values
+/// defined for `stream_content` 0x3 in _ETSI EN 300 468_ table 26.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SubtitlingType {
+    Reserved(u8),
+    EbuTeletext,
+    AssociatedEbuTeletext,
+    VbiData,
+    /// DVB subtitles (normal) with no monitor aspect ratio criticality
+    DvbNormal,
+    /// DVB subtitles (normal) for display on a 4:3 aspect ratio monitor
+    DvbNormal4x3,
+    /// DVB subtitles (normal) for display on a 16:9 aspect ratio monitor
+    DvbNormal16x9,
+    /// DVB subtitles (normal) for display on a 2.21:1 aspect ratio monitor
+    DvbNormal221x1,
+    /// DVB subtitles (normal) for display on a high definition monitor
+    DvbNormalHd,
+    /// DVB subtitles (normal) with plano-stereoscopic disparity for display on a high
+    /// definition monitor
+    DvbNormalStereoscopicHd,
+    /// DVB subtitles (normal) for display on an ultra high definition monitor
+    DvbNormalUhd,
+    /// DVB subtitles (for the hard of hearing) with no monitor aspect ratio criticality
+    DvbHardOfHearing,
+    /// DVB subtitles (for the hard of hearing) for display on a 4:3 aspect ratio monitor
+    DvbHardOfHearing4x3,
+    /// DVB subtitles (for the hard of hearing) for display on a 16:9 aspect ratio monitor
+    DvbHardOfHearing16x9,
+    /// DVB subtitles (for the hard of hearing) for display on a 2.21:1 aspect ratio monitor
+    DvbHardOfHearing221x1,
+    /// DVB subtitles (for the hard of hearing) for display on a high definition monitor
+    DvbHardOfHearingHd,
+    /// DVB subtitles (for the hard of hearing) with plano-stereoscopic disparity for display on
+    /// a high definition monitor
+    DvbHardOfHearingStereoscopicHd,
+    /// DVB subtitles (for the hard of hearing) for display on an ultra high definition monitor
+    DvbHardOfHearingUhd,
+    /// Open (in-vision) sign language interpretation for the deaf
+    OpenSignLanguage,
+    /// Closed sign language interpretation for the deaf
+    ClosedSignLanguage,
+    UserDefined(u8),
+}
+impl SubtitlingType {
+    pub fn from_id(id: u8) -> SubtitlingType {
+        match id {
+            0x01 => SubtitlingType::EbuTeletext,
+            0x02 => SubtitlingType::AssociatedEbuTeletext,
+            0x03 => SubtitlingType::VbiData,
+            0x10 => SubtitlingType::DvbNormal,
+            0x11 => SubtitlingType::DvbNormal4x3,
+            0x12 => SubtitlingType::DvbNormal16x9,
+            0x13 => SubtitlingType::DvbNormal221x1,
+            0x14 => SubtitlingType::DvbNormalHd,
+            0x15 => SubtitlingType::DvbNormalStereoscopicHd,
+            0x16 => SubtitlingType::DvbNormalUhd,
+            0x20 => SubtitlingType::DvbHardOfHearing,
+            0x21 => SubtitlingType::DvbHardOfHearing4x3,
+            0x22 => SubtitlingType::DvbHardOfHearing16x9,
+            0x23 => SubtitlingType::DvbHardOfHearing221x1,
+            0x24 => SubtitlingType::DvbHardOfHearingHd,
+            0x25 => SubtitlingType::DvbHardOfHearingStereoscopicHd,
+            0x26 => SubtitlingType::DvbHardOfHearingUhd,
+            0x30 => SubtitlingType::OpenSignLanguage,
+            0x31 => SubtitlingType::ClosedSignLanguage,
+            0xb0..=0xfe => SubtitlingType::UserDefined(id),
+            _ => SubtitlingType::Reserved(id),
+        }
+    }
+
+    /// `true` if the subtitles are to be decoded by a DVB subtitle decoder (ETSI EN 300 743),
+    /// rather than being Teletext or sign language.
+    pub fn is_dvb(&self) -> bool {
+        matches!(
+            self,
+            SubtitlingType::DvbNormal
+                | SubtitlingType::DvbNormal4x3
+                | SubtitlingType::DvbNormal16x9
+                | SubtitlingType::DvbNormal221x1
+                | SubtitlingType::DvbNormalHd
+                | SubtitlingType::DvbNormalStereoscopicHd
+                | SubtitlingType::DvbNormalUhd
+        ) || self.is_hard_of_hearing()
+    }
+
+    /// `true` for DVB subtitles intended for the hard of hearing.
+    pub fn is_hard_of_hearing(&self) -> bool {
+        matches!(
+            self,
+            SubtitlingType::DvbHardOfHearing
+                | SubtitlingType::DvbHardOfHearing4x3
+                | SubtitlingType::DvbHardOfHearing16x9
+                | SubtitlingType::DvbHardOfHearing221x1
+                | SubtitlingType::DvbHardOfHearingHd
+                | SubtitlingType::DvbHardOfHearingStereoscopicHd
+                | SubtitlingType::DvbHardOfHearingUhd
+        )
+    }
+}
+
+/// A single 8-byte entry from a [`SubtitlingDescriptor`].
+pub struct SubtitlingEntry<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> SubtitlingEntry<'buf> {
+    const SIZE: usize = 8;
+
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn subtitling_type(&self) -> SubtitlingType {
+        SubtitlingType::from_id(self.data[3])
+    }
+
+    /// Identifies the subtitle segments to be decoded for this language, as defined in
+    /// _ETSI EN 300 743_.
+    pub fn composition_page_id(&self) -> u16 {
+        u16::from(self.data[4]) << 8 | u16::from(self.data[5])
+    }
+
+    /// Identifies the subtitle segments that may be shared by several subtitle services, as
+    /// defined in _ETSI EN 300 743_.  Equal to the `composition_page_id` if no such shared
+    /// segments are used.
+    pub fn ancillary_page_id(&self) -> u16 {
+        u16::from(self.data[6]) << 8 | u16::from(self.data[7])
+    }
+}
+impl<'buf> fmt::Debug for SubtitlingEntry<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("SubtitlingEntry")
+            .field("language_code", &self.language_code_str())
+            .field("subtitling_type", &self.subtitling_type())
+            .field("composition_page_id", &self.composition_page_id())
+            .field("ancillary_page_id", &self.ancillary_page_id())
+            .finish()
+    }
+}
+
+/// Identifies the subtitle services carried in a DVB subtitle elementary stream, as defined in
+/// _ETSI EN 300 468_ section 6.2.41.
+pub struct SubtitlingDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> SubtitlingDescriptor<'buf> {
+    pub const TAG: u8 = 0x59;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<SubtitlingDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(SubtitlingDescriptor { data })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = SubtitlingEntry<'buf>> {
+        self.data
+            .chunks_exact(SubtitlingEntry::SIZE)
+            .map(|data| SubtitlingEntry { data })
+    }
+}
+impl<'buf> fmt::Debug for SubtitlingDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("SubtitlingDescriptor")
+            .field("entries", &self.entries().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// What a [`TtmlSubtitlingDescriptor`] subtitle stream provides, as given in
+/// _ETSI EN 303 560_ table 2.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SubtitlePurpose {
+    /// Dialogue in the same language as the audio (e.g. for use with the audio muted)
+    SameLanguageDialogue,
+    /// Translation of dialogue in another language
+    OtherLanguageDialogue,
+    /// All dialogue, whatever its language
+    AllDialogue,
+    /// Dialogue and sound effects, for the hard of hearing
+    HardOfHearing,
+    /// Translation of dialogue in another language, with hard of hearing annotation
+    OtherLanguageDialogueHardOfHearing,
+    /// All dialogue, with hard of hearing annotation
+    AllDialogueHardOfHearing,
+    /// Audio description, intended to be rendered as speech by the receiver
+    AudioDescription,
+    /// Commentary relating to the content
+    ContentRelatedCommentary,
+    Reserved(u8),
+}
+impl SubtitlePurpose {
+    pub fn from_id(id: u8) -> SubtitlePurpose {
+        match id {
+            0x00 => SubtitlePurpose::SameLanguageDialogue,
+            0x01 => SubtitlePurpose::OtherLanguageDialogue,
+            0x02 => SubtitlePurpose::AllDialogue,
+            0x10 => SubtitlePurpose::HardOfHearing,
+            0x11 => SubtitlePurpose::OtherLanguageDialogueHardOfHearing,
+            0x12 => SubtitlePurpose::AllDialogueHardOfHearing,
+            0x30 => SubtitlePurpose::AudioDescription,
+            0x31 => SubtitlePurpose::ContentRelatedCommentary,
+            0x03..=0x0F | 0x13..=0x2F | 0x32..=0x3F => SubtitlePurpose::Reserved(id),
+            _ => panic!(
+                "Invalid subtitle_purpose value {} (must be less than 0x40)",
+                id
+            ),
+        }
+    }
+
+    /// `true` for subtitles annotated for the hard of hearing.
+    pub fn is_hard_of_hearing(&self) -> bool {
+        matches!(
+            self,
+            SubtitlePurpose::HardOfHearing
+                | SubtitlePurpose::OtherLanguageDialogueHardOfHearing
+                | SubtitlePurpose::AllDialogueHardOfHearing
+        )
+    }
+}
+
+/// Whether a [`TtmlSubtitlingDescriptor`] subtitle stream is suitable to be rendered as speech
+/// by a text-to-speech engine
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TtsSuitability {
+    Unknown,
+    Suitable,
+    NotSuitable,
+    Reserved,
+}
+impl TtsSuitability {
+    pub fn from_id(id: u8) -> TtsSuitability {
+        match id {
+            0 => TtsSuitability::Unknown,
+            1 => TtsSuitability::Suitable,
+            2 => TtsSuitability::NotSuitable,
+            3 => TtsSuitability::Reserved,
+            _ => panic!("Invalid TTS_suitability value {} (must be less than 4)", id),
+        }
+    }
+}
+
+/// Identifies a TTML (e.g. EBU-TT-D) subtitle stream, as defined in _ETSI EN 303 560_ section
+/// 5.2.1.
+///
+/// This is carried within an [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct TtmlSubtitlingDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TtmlSubtitlingDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x20;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<TtmlSubtitlingDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        let not_enough = |expected| descriptor::DescriptorError::NotEnoughData {
+            tag: crate::extension::ExtensionDescriptor::TAG,
+            actual: data.len(),
+            expected,
+        };
+        if data.len() < 5 {
+            return Err(not_enough(5));
+        }
+        let mut pos = 5 + usize::from(data[4] & 0b1111);
+        if data[4] & 0b0100_0000 != 0 {
+            pos += 4;
+        }
+        if data[4] & 0b1000_0000 != 0 {
+            let font_count = data.get(pos).ok_or_else(|| not_enough(pos + 1))?;
+            pos += 1 + usize::from(*font_count);
+        }
+        let text_length = data.get(pos).ok_or_else(|| not_enough(pos + 1))?;
+        pos += 1 + usize::from(*text_length);
+        if data.len() < pos {
+            return Err(not_enough(pos));
+        }
+        Ok(TtmlSubtitlingDescriptor { data })
+    }
+
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn subtitle_purpose(&self) -> SubtitlePurpose {
+        SubtitlePurpose::from_id(self.data[3] >> 2)
+    }
+
+    pub fn tts_suitability(&self) -> TtsSuitability {
+        TtsSuitability::from_id(self.data[3] & 0b11)
+    }
+
+    fn essential_font_usage_flag(&self) -> bool {
+        self.data[4] & 0b1000_0000 != 0
+    }
+
+    fn qualifier_present_flag(&self) -> bool {
+        self.data[4] & 0b0100_0000 != 0
+    }
+
+    /// The DVB TTML profiles to which the subtitle documents conform, using the `dvb_ttml_profile`
+    /// values of _ETSI EN 303 560_ table 3.
+    pub fn dvb_ttml_profiles(&self) -> &'buf [u8] {
+        &self.data[5..5 + usize::from(self.data[4] & 0b1111)]
+    }
+
+    fn qualifier_pos(&self) -> usize {
+        5 + self.dvb_ttml_profiles().len()
+    }
+
+    fn font_pos(&self) -> usize {
+        if self.qualifier_present_flag() {
+            self.qualifier_pos() + 4
+        } else {
+            self.qualifier_pos()
+        }
+    }
+
+    fn text_pos(&self) -> usize {
+        if self.essential_font_usage_flag() {
+            self.font_pos() + 1 + self.essential_fonts().len()
+        } else {
+            self.font_pos()
+        }
+    }
+
+    /// Further describes the subtitles' presentation (e.g. their sizing and timing), as defined
+    /// in _ETSI EN 303 560_ table 4
+    pub fn qualifier(&self) -> Option<u32> {
+        if self.qualifier_present_flag() {
+            let pos = self.qualifier_pos();
+            Some(
+                u32::from(self.data[pos]) << 24
+                    | u32::from(self.data[pos + 1]) << 16
+                    | u32::from(self.data[pos + 2]) << 8
+                    | u32::from(self.data[pos + 3]),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn essential_fonts(&self) -> &'buf [u8] {
+        if self.essential_font_usage_flag() {
+            let pos = self.font_pos();
+            let font_count = usize::from(self.data[pos]);
+            &self.data[pos + 1..pos + 1 + font_count]
+        } else {
+            &[]
+        }
+    }
+
+    /// The 7-bit `essential_font_id` values of downloadable fonts which must be available to
+    /// present the subtitles, as listed in the `downloadable_font_descriptor` of the service;
+    /// empty if no such fonts are required
+    pub fn essential_font_ids(&self) -> impl Iterator<Item = u8> + 'buf {
+        self.essential_fonts().iter().map(|id| id & 0b0111_1111)
+    }
+
+    /// A name for the subtitle service, suitable for presentation to the user
+    pub fn text(&self) -> Result<Text<'buf>, TextError> {
+        let pos = self.text_pos();
+        let text_length = usize::from(self.data[pos]);
+        Text::new(&self.data[pos + 1..pos + 1 + text_length])
+    }
+}
+impl<'buf> fmt::Debug for TtmlSubtitlingDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TtmlSubtitlingDescriptor")
+            .field("language_code", &self.language_code_str())
+            .field("subtitle_purpose", &self.subtitle_purpose())
+            .field("tts_suitability", &self.tts_suitability())
+            .field("dvb_ttml_profiles", &self.dvb_ttml_profiles())
+            .field("qualifier", &self.qualifier())
+            .field(
+                "essential_font_ids",
+                &self.essential_font_ids().collect::<Vec<_>>(),
+            )
+            .field("text", &self.text())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn subtitling_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            b'e', b'n', b'g', 0x10, 0x00, 0x02, 0x00, 0x02,
+            b'e', b'n', b'g', 0x24, 0x00, 0x03, 0x00, 0x01,
+            b'c', b'y', b'm', 0xb3, 0x00, 0x04, 0x00, 0x04,
+        ];
+        let desc = SubtitlingDescriptor::new(0x59, &data).unwrap();
+        let entries: Vec<_> = desc.entries().collect();
+        assert_eq!(3, entries.len());
+
+        assert_eq!(Some("eng"), entries[0].language_code_str());
+        assert_eq!(SubtitlingType::DvbNormal, entries[0].subtitling_type());
+        assert!(entries[0].subtitling_type().is_dvb());
+        assert!(!entries[0].subtitling_type().is_hard_of_hearing());
+        assert_eq!(2, entries[0].composition_page_id());
+        assert_eq!(2, entries[0].ancillary_page_id());
+
+        assert_eq!(
+            SubtitlingType::DvbHardOfHearingHd,
+            entries[1].subtitling_type()
+        );
+        assert!(entries[1].subtitling_type().is_dvb());
+        assert!(entries[1].subtitling_type().is_hard_of_hearing());
+        assert_eq!(3, entries[1].composition_page_id());
+        assert_eq!(1, entries[1].ancillary_page_id());
+
+        assert_eq!(Some("cym"), entries[2].language_code_str());
+        assert_eq!(
+            SubtitlingType::UserDefined(0xb3),
+            entries[2].subtitling_type()
+        );
+        assert!(!entries[2].subtitling_type().is_dvb());
+    }
+
+    #[test]
+    fn ttml_subtitling_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            b'e', b'n', b'g',
+            0b0100_0010, // hard of hearing, not suitable for TTS
+            0b1100_0001, // essential fonts, qualifier and 1 profile
+            0x01,
+            0x12, 0x34, 0x56, 0x78, // qualifier
+            0x02, 0x85, 0x7F, // 2 essential fonts, the first with reserved bit set
+            0x02, b'H', b'I',
+            0x00, // reserved_zero_future_use
+        ];
+        let desc = TtmlSubtitlingDescriptor::new(0x20, &data).unwrap();
+        assert_eq!(Some("eng"), desc.language_code_str());
+        assert_eq!(SubtitlePurpose::HardOfHearing, desc.subtitle_purpose());
+        assert!(desc.subtitle_purpose().is_hard_of_hearing());
+        assert_eq!(TtsSuitability::NotSuitable, desc.tts_suitability());
+        assert_eq!(&[0x01], desc.dvb_ttml_profiles());
+        assert_eq!(Some(0x1234_5678), desc.qualifier());
+        assert_eq!(
+            vec![0x05, 0x7F],
+            desc.essential_font_ids().collect::<Vec<_>>()
+        );
+        assert_eq!("HI", desc.text().unwrap().to_string().unwrap());
+        assert!(TtmlSubtitlingDescriptor::new(0x20, &data[..15]).is_err());
+
+        let data = [b'c', b'y', b'm', 0b0000_0001, 0x00, 0x00];
+        let desc = TtmlSubtitlingDescriptor::new(0x20, &data).unwrap();
+        assert_eq!(
+            SubtitlePurpose::SameLanguageDialogue,
+            desc.subtitle_purpose()
+        );
+        assert_eq!(TtsSuitability::Suitable, desc.tts_suitability());
+        assert!(desc.dvb_ttml_profiles().is_empty());
+        assert_eq!(None, desc.qualifier());
+        assert_eq!(0, desc.essential_font_ids().count());
+        assert!(TtmlSubtitlingDescriptor::new(0x20, &data[..5]).is_err());
+    }
+}
@@ -0,0 +1,549 @@
+//! _Target Region Descriptor_ (extension tag 0x09) and _Target Region Name Descriptor_ (extension
+//! tag 0x0A)
+//!
+//! Target regions form a hierarchy: a country is divided into primary regions, which may be
+//! divided into secondary regions, which may in turn be divided into tertiary regions.  Regional
+//! variants of a service use the target region descriptor to indicate the regions for which they
+//! are intended, and [`best_match()`] can be used to pick the variant most specific to the region
+//! configured in a receiver.  [`RegionNames`] collects the names of regions, for presentation in
+//! a region selection menu.
+use crate::multilingual::language_matches;
+use crate::{Text, TextError};
+use mpeg2ts_reader::descriptor;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Identifies a region at some level of the target region hierarchy
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct TargetRegion {
+    /// Three-character ISO 3166 country code
+    pub country_code: [u8; 3],
+    pub primary_region_code: Option<u8>,
+    pub secondary_region_code: Option<u8>,
+    pub tertiary_region_code: Option<u16>,
+}
+impl TargetRegion {
+    /// A region covering the whole of the given country (a three-character ISO 3166 code)
+    pub fn country(country_code: [u8; 3]) -> TargetRegion {
+        TargetRegion {
+            country_code,
+            primary_region_code: None,
+            secondary_region_code: None,
+            tertiary_region_code: None,
+        }
+    }
+
+    /// The country code as a string, or `None` if the bytes are not valid UTF-8
+    pub fn country_code_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.country_code).ok()
+    }
+
+    /// The level of this region in the hierarchy: `0` for a whole country, through to `3` for a
+    /// tertiary region
+    pub fn depth(&self) -> u8 {
+        if self.tertiary_region_code.is_some() {
+            3
+        } else if self.secondary_region_code.is_some() {
+            2
+        } else if self.primary_region_code.is_some() {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// The region one level up the hierarchy, or `None` for a whole country
+    pub fn parent(&self) -> Option<TargetRegion> {
+        let mut parent = *self;
+        match self.depth() {
+            0 => return None,
+            1 => parent.primary_region_code = None,
+            2 => parent.secondary_region_code = None,
+            _ => parent.tertiary_region_code = None,
+        }
+        Some(parent)
+    }
+
+    /// `true` if `other` is this region, or lies within it.  Country codes are compared
+    /// case-insensitively.
+    pub fn contains(&self, other: &TargetRegion) -> bool {
+        fn level<T: PartialEq>(mine: Option<T>, theirs: Option<T>) -> bool {
+            mine.is_none() || mine == theirs
+        }
+        self.country_code.eq_ignore_ascii_case(&other.country_code)
+            && level(self.primary_region_code, other.primary_region_code)
+            && level(self.secondary_region_code, other.secondary_region_code)
+            && level(self.tertiary_region_code, other.tertiary_region_code)
+    }
+}
+
+fn country_code(data: &[u8]) -> [u8; 3] {
+    [data[0], data[1], data[2]]
+}
+
+/// Reads the region codes following an entry's header, returning the region and the number of
+/// bytes consumed, or `Err` giving the number of bytes the codes require if `data` is too short
+fn read_region_codes(
+    country_code: [u8; 3],
+    region_depth: u8,
+    data: &[u8],
+) -> Result<(TargetRegion, usize), usize> {
+    let len = match region_depth {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        _ => 4,
+    };
+    if data.len() < len {
+        return Err(len);
+    }
+    let mut region = TargetRegion::country(country_code);
+    if region_depth >= 1 {
+        region.primary_region_code = Some(data[0]);
+    }
+    if region_depth >= 2 {
+        region.secondary_region_code = Some(data[1]);
+    }
+    if region_depth >= 3 {
+        region.tertiary_region_code = Some(u16::from(data[2]) << 8 | u16::from(data[3]));
+    }
+    Ok((region, len))
+}
+
+/// Reads one entry of a target region descriptor, returning the region and the entry length, or
+/// `Err` giving the length the entry requires if it is incomplete
+fn read_target_region(
+    default_country: [u8; 3],
+    data: &[u8],
+) -> Result<(TargetRegion, usize), usize> {
+    let flags = *data.first().ok_or(1usize)?;
+    let mut pos = 1;
+    let mut country = default_country;
+    if flags & 0b100 != 0 {
+        country = country_code(data.get(1..4).ok_or(4usize)?);
+        pos += 3;
+    }
+    let (region, len) =
+        read_region_codes(country, flags & 0b11, &data[pos..]).map_err(|len| pos + len)?;
+    Ok((region, pos + len))
+}
+
+/// Identifies the regions for which a service (or all the services of a transport stream,
+/// bouquet or network) is intended, as defined in _ETSI EN 300 468_ section 6.4.12.
+///
+/// This is carried within an
+/// [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct TargetRegionDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TargetRegionDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x09;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<TargetRegionDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        if data.len() < 3 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected: 3,
+            });
+        }
+        let mut pos = 3;
+        while pos < data.len() {
+            match read_target_region(country_code(data), &data[pos..]) {
+                Ok((_, len)) => pos += len,
+                Err(required) => {
+                    return Err(descriptor::DescriptorError::NotEnoughData {
+                        tag: crate::extension::ExtensionDescriptor::TAG,
+                        actual: data.len(),
+                        expected: pos + required,
+                    })
+                }
+            }
+        }
+        Ok(TargetRegionDescriptor { data })
+    }
+
+    /// Three-character ISO 3166 country code, which applies to all regions that do not specify
+    /// their own
+    pub fn country_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// The country code as a string, or `None` if the bytes are not valid UTF-8
+    pub fn country_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.country_code()).ok()
+    }
+
+    /// The targeted regions.  If the descriptor lists no regions, the whole country given by
+    /// [`country_code()`](#method.country_code) is targeted, and a single region for that
+    /// country is produced.
+    pub fn regions(&self) -> impl Iterator<Item = TargetRegion> + 'buf {
+        let default_country = country_code(self.data);
+        let mut remaining = &self.data[3..];
+        let mut whole_country = remaining.is_empty();
+        std::iter::from_fn(move || {
+            if whole_country {
+                whole_country = false;
+                return Some(TargetRegion::country(default_country));
+            }
+            if remaining.is_empty() {
+                return None;
+            }
+            // lengths were checked by TargetRegionDescriptor::new()
+            let (region, len) = read_target_region(default_country, remaining).unwrap();
+            remaining = &remaining[len..];
+            Some(region)
+        })
+    }
+}
+impl<'buf> fmt::Debug for TargetRegionDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TargetRegionDescriptor")
+            .field("country_code", &self.country_code_str())
+            .field("regions", &self.regions().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The most specific region among those targeted by the given descriptors which contains the
+/// `receiver` region (i.e. the region configured by the viewer), or `None` if no targeted region
+/// contains it.
+///
+/// When choosing between regional variants of a service, the variant giving the deepest match
+/// should be preferred.
+pub fn best_match<'a, 'buf: 'a>(
+    descriptors: impl IntoIterator<Item = &'a TargetRegionDescriptor<'buf>>,
+    receiver: &TargetRegion,
+) -> Option<TargetRegion> {
+    descriptors
+        .into_iter()
+        .flat_map(|d| d.regions())
+        .filter(|r| r.contains(receiver))
+        .max_by_key(|r| r.depth())
+}
+
+/// Decides if a service is intended for the `receiver` region, given all the
+/// [`TargetRegionDescriptor`] instances applying to it.  A service with no target region
+/// descriptors is not targeted at any particular region, and so is intended for all of them.
+pub fn is_targeted<'a, 'buf: 'a>(
+    descriptors: impl IntoIterator<Item = &'a TargetRegionDescriptor<'buf>>,
+    receiver: &TargetRegion,
+) -> bool {
+    let mut descriptors = descriptors.into_iter().peekable();
+    descriptors.peek().is_none() || best_match(descriptors, receiver).is_some()
+}
+
+/// The name of one region, from a [`TargetRegionNameDescriptor`]
+pub struct RegionName<'buf> {
+    country_code: [u8; 3],
+    data: &'buf [u8],
+}
+impl<'buf> RegionName<'buf> {
+    fn region_name_length(&self) -> usize {
+        usize::from(self.data[0] & 0b11_1111)
+    }
+
+    pub fn region_name(&self) -> Result<Text<'buf>, TextError> {
+        Text::new(&self.data[1..1 + self.region_name_length()])
+    }
+
+    pub fn region(&self) -> TargetRegion {
+        // region_depth values 0 and 1 both give just a primary region code
+        let depth = (self.data[0] >> 6).max(1);
+        let codes = &self.data[1 + self.region_name_length()..];
+        // lengths were checked by TargetRegionNameDescriptor::new()
+        read_region_codes(self.country_code, depth, codes)
+            .unwrap()
+            .0
+    }
+}
+impl<'buf> fmt::Debug for RegionName<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("RegionName")
+            .field("region", &self.region())
+            .field("region_name", &self.region_name())
+            .finish()
+    }
+}
+
+/// Length of the region name entry at the start of `data`, or `Err` giving the length the entry
+/// requires if it is incomplete
+fn region_name_len(data: &[u8]) -> Result<usize, usize> {
+    let header = *data.first().ok_or(1usize)?;
+    let codes_pos = 1 + usize::from(header & 0b11_1111);
+    let depth = (header >> 6).max(1);
+    let codes = data.get(codes_pos..).unwrap_or(&[]);
+    let (_, codes_len) = read_region_codes(*b"   ", depth, codes).map_err(|len| codes_pos + len)?;
+    Ok(codes_pos + codes_len)
+}
+
+/// Gives the names, in one language, of regions of the target region hierarchy of a country, as
+/// defined in _ETSI EN 300 468_ section 6.4.13.
+///
+/// This is carried within an
+/// [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct TargetRegionNameDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TargetRegionNameDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x0A;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<TargetRegionNameDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        if data.len() < 6 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected: 6,
+            });
+        }
+        let mut pos = 6;
+        while pos < data.len() {
+            match region_name_len(&data[pos..]) {
+                Ok(len) => pos += len,
+                Err(required) => {
+                    return Err(descriptor::DescriptorError::NotEnoughData {
+                        tag: crate::extension::ExtensionDescriptor::TAG,
+                        actual: data.len(),
+                        expected: pos + required,
+                    })
+                }
+            }
+        }
+        Ok(TargetRegionNameDescriptor { data })
+    }
+
+    /// Three-character ISO 3166 country code
+    pub fn country_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// The country code as a string, or `None` if the bytes are not valid UTF-8
+    pub fn country_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.country_code()).ok()
+    }
+
+    /// Three-character ISO 639-2 language code of the region names
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[3..6]
+    }
+
+    /// The language code as a string, or `None` if the bytes are not valid UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn region_names(&self) -> impl Iterator<Item = RegionName<'buf>> + 'buf {
+        let country_code = country_code(self.data);
+        let mut remaining = &self.data[6..];
+        std::iter::from_fn(move || {
+            if remaining.is_empty() {
+                return None;
+            }
+            // lengths were checked by TargetRegionNameDescriptor::new()
+            let (head, tail) = remaining.split_at(region_name_len(remaining).unwrap());
+            remaining = tail;
+            Some(RegionName {
+                country_code,
+                data: head,
+            })
+        })
+    }
+}
+impl<'buf> fmt::Debug for TargetRegionNameDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TargetRegionNameDescriptor")
+            .field("country_code", &self.country_code_str())
+            .field("language_code", &self.language_code_str())
+            .field("region_names", &self.region_names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Collects the region names given by [`TargetRegionNameDescriptor`] instances (usually from the
+/// NIT or BAT), and provides the hierarchy of the named regions.
+#[derive(Default)]
+pub struct RegionNames {
+    names: HashMap<TargetRegion, Vec<([u8; 3], String)>>,
+}
+impl RegionNames {
+    pub fn new() -> RegionNames {
+        RegionNames::default()
+    }
+
+    /// Record the names given by the descriptor, replacing any previously recorded names for the
+    /// same regions in the same language.  Names which cannot be decoded are ignored.
+    pub fn add(&mut self, desc: &TargetRegionNameDescriptor<'_>) {
+        let language = country_code(desc.language_code());
+        for name in desc.region_names() {
+            let text = match name
+                .region_name()
+                .and_then(|n| n.to_string_with_replacement().map(|s| s.into_owned()))
+            {
+                Ok(text) => text,
+                Err(_) => continue,
+            };
+            let names = self.names.entry(name.region()).or_default();
+            match names.iter_mut().find(|(lang, _)| *lang == language) {
+                Some(entry) => entry.1 = text,
+                None => names.push((language, text)),
+            }
+        }
+    }
+
+    /// The name of the given region in the first of the `preferred_languages` (three-character
+    /// ISO 639-2 codes, compared case-insensitively) for which a name is available, or otherwise
+    /// in any available language.
+    pub fn name(&self, region: &TargetRegion, preferred_languages: &[&str]) -> Option<&str> {
+        let names = self.names.get(region)?;
+        preferred_languages
+            .iter()
+            .find_map(|pref| names.iter().find(|(lang, _)| language_matches(lang, pref)))
+            .or_else(|| names.first())
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// All named regions
+    pub fn regions(&self) -> impl Iterator<Item = &TargetRegion> {
+        self.names.keys()
+    }
+
+    /// The named regions one level below the given region in the hierarchy (e.g. the primary
+    /// regions of a country), ordered by their region codes.
+    pub fn children(&self, parent: &TargetRegion) -> Vec<TargetRegion> {
+        let mut children: Vec<_> = self
+            .names
+            .keys()
+            .filter(|r| r.depth() == parent.depth() + 1 && parent.contains(r))
+            .copied()
+            .collect();
+        children.sort_by_key(|r| {
+            (
+                r.primary_region_code,
+                r.secondary_region_code,
+                r.tertiary_region_code,
+            )
+        });
+        children
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn region(primary: Option<u8>, secondary: Option<u8>, tertiary: Option<u16>) -> TargetRegion {
+        TargetRegion {
+            country_code: *b"GBR",
+            primary_region_code: primary,
+            secondary_region_code: secondary,
+            tertiary_region_code: tertiary,
+        }
+    }
+
+    #[test]
+    fn target_region_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            b'G', b'B', b'R',
+            0b1111_1001, 0x01,
+            0b1111_1010, 0x01, 0x03,
+            0b1111_1111, b'I', b'R', b'L', 0x02, 0x04, 0x12, 0x34,
+        ];
+        let desc = TargetRegionDescriptor::new(0x09, &data).unwrap();
+        let regions: Vec<_> = desc.regions().collect();
+        assert_eq!(
+            vec![
+                region(Some(1), None, None),
+                region(Some(1), Some(3), None),
+                TargetRegion {
+                    country_code: *b"IRL",
+                    primary_region_code: Some(2),
+                    secondary_region_code: Some(4),
+                    tertiary_region_code: Some(0x1234),
+                },
+            ],
+            regions
+        );
+        let expected = |len| match TargetRegionDescriptor::new(0x09, &data[..len]) {
+            Err(descriptor::DescriptorError::NotEnoughData { expected, .. }) => expected,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(3, expected(2));
+        assert_eq!(5, expected(4));
+        assert_eq!(12, expected(9));
+        assert_eq!(16, expected(12));
+        assert_eq!(16, expected(15));
+
+        let desc = TargetRegionDescriptor::new(0x09, b"GBR").unwrap();
+        assert_eq!(
+            vec![TargetRegion::country(*b"GBR")],
+            desc.regions().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn matching() {
+        // two regional variants of a service, one for primary region 1 as a whole, and one for
+        // its secondary region 3
+        let broad = [b'G', b'B', b'R', 0b1111_1001, 0x01];
+        let broad = TargetRegionDescriptor::new(0x09, &broad).unwrap();
+        let narrow = [b'G', b'B', b'R', 0b1111_1010, 0x01, 0x03];
+        let narrow = TargetRegionDescriptor::new(0x09, &narrow).unwrap();
+
+        let receiver = region(Some(1), Some(3), Some(7));
+        assert_eq!(Some(1), best_match([&broad], &receiver).map(|r| r.depth()));
+        assert_eq!(Some(2), best_match([&narrow], &receiver).map(|r| r.depth()));
+
+        let receiver = region(Some(1), Some(2), None);
+        assert!(is_targeted([&broad], &receiver));
+        assert!(!is_targeted([&narrow], &receiver));
+        assert!(is_targeted(std::iter::empty(), &receiver));
+        assert!(!is_targeted([&broad], &region(Some(2), None, None)));
+    }
+
+    #[test]
+    fn region_names() {
+        #[rustfmt::skip]
+        let eng = [
+            b'G', b'B', b'R', b'e', b'n', b'g',
+            0x01 << 6 | 5, b'W', b'a', b'l', b'e', b's', 0x01,
+            0x02 << 6 | 5, b'C', b'a', b'r', b'd', b'y', 0x01, 0x02,
+            0x01 << 6 | 7, b'E', b'n', b'g', b'l', b'a', b'n', b'd', 0x02,
+        ];
+        #[rustfmt::skip]
+        let cym = [
+            b'G', b'B', b'R', b'c', b'y', b'm',
+            0x01 << 6 | 5, b'C', b'y', b'm', b'r', b'u', 0x01,
+        ];
+        let mut names = RegionNames::new();
+        names.add(&TargetRegionNameDescriptor::new(0x0A, &eng).unwrap());
+        names.add(&TargetRegionNameDescriptor::new(0x0A, &cym).unwrap());
+        let expected = |len| match TargetRegionNameDescriptor::new(0x0A, &eng[..len]) {
+            Err(descriptor::DescriptorError::NotEnoughData { expected, .. }) => expected,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(6, expected(4));
+        assert_eq!(13, expected(8));
+        assert_eq!(21, expected(15));
+        assert_eq!(30, expected(29));
+
+        let wales = region(Some(1), None, None);
+        assert_eq!(Some("Cymru"), names.name(&wales, &["CYM", "eng"]));
+        assert_eq!(Some("Wales"), names.name(&wales, &["eng"]));
+        assert_eq!(Some("Wales"), names.name(&wales, &[]));
+        assert_eq!(
+            vec![wales, region(Some(2), None, None)],
+            names.children(&TargetRegion::country(*b"GBR"))
+        );
+        assert_eq!(vec![region(Some(1), Some(2), None)], names.children(&wales));
+        assert_eq!(Some(wales), region(Some(1), Some(2), None).parent());
+    }
+}
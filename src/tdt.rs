@@ -1,15 +1,20 @@
-//! _Time and Date Table_ section data (EN 300 468 §5.2.5).
+//! _Time and Date Table_ (EN 300 468 §5.2.5) and _Time Offset Table_ (EN 300 468 §5.2.6)
+//! section data.
 //!
 //! TDT carries UTC time for the multiplex. It uses compact syntax
 //! (no section syntax indicator, no CRC) on PID 0x14 with table_id 0x70.
+//!
+//! TOT shares the PID and carries the same UTC time followed by a descriptor loop (normally a
+//! single local_time_offset_descriptor).  It has table_id 0x73 and, unlike TDT, ends with a CRC.
 
 use crate::time::{MjdTimestamp, MjdTimestampError};
-use mpeg2ts_reader::{demultiplex, packet, psi};
+use mpeg2ts_reader::{demultiplex, descriptor, packet, psi};
 use std::marker;
 
 pub const TDT_PID: packet::Pid = packet::Pid::new(0x14);
 
 const TDT_TABLE_ID: u8 = 0x70;
+const TOT_TABLE_ID: u8 = 0x73;
 
 /// A parsed TDT section: just a 5-byte MJD+BCD UTC timestamp.
 pub struct TdtSection<'buf> {
@@ -22,13 +27,39 @@ impl<'buf> TdtSection<'buf> {
     }
 }
 
-/// Consumer trait for receiving parsed TDT sections.
+/// A parsed TOT section: the 5-byte MJD+BCD UTC timestamp followed by descriptors.
+pub struct TotSection<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TotSection<'buf> {
+    /// Parse the 5-byte timestamp from the section body.
+    pub fn utc_time(&self) -> Result<MjdTimestamp<'buf>, MjdTimestampError> {
+        MjdTimestamp::new(&self.data[..5])
+    }
+
+    fn descriptors_loop_length(&self) -> usize {
+        usize::from(self.data[5] & 0b1111) << 8 | usize::from(self.data[6])
+    }
+
+    /// The descriptors of this section, normally a single
+    /// [`LocalTimeOffsetDescriptor`](../local_time_offset/struct.LocalTimeOffsetDescriptor.html).
+    pub fn descriptors<Desc: descriptor::Descriptor<'buf>>(
+        &self,
+    ) -> descriptor::DescriptorIter<'buf, Desc> {
+        let start = 7;
+        let end = start + self.descriptors_loop_length();
+        descriptor::DescriptorIter::new(&self.data[start..end])
+    }
+}
+
+/// Consumer trait for receiving parsed TDT (and optionally TOT) sections.
 pub trait TdtConsumer<Ctx> {
     fn tdt(&mut self, ctx: &mut Ctx, section: &TdtSection<'_>);
+    fn tot(&mut self, _ctx: &mut Ctx, _section: &TotSection<'_>) {}
 }
 
 /// Compact-syntax parser that validates the table_id and delivers
-/// [`TdtSection`] values to the consumer.
+/// [`TdtSection`] and [`TotSection`] values to the consumer.
 pub struct TdtProcessor<Ctx: demultiplex::DemuxContext, C: TdtConsumer<Ctx>> {
     phantom: marker::PhantomData<Ctx>,
     consumer: C,
@@ -49,17 +80,43 @@ impl<Ctx: demultiplex::DemuxContext, C: TdtConsumer<Ctx>> psi::WholeCompactSynta
     type Context = Ctx;
 
     fn section(&mut self, ctx: &mut Self::Context, header: &psi::SectionCommonHeader, data: &[u8]) {
-        if header.table_id != TDT_TABLE_ID {
-            // PID 0x14 also carries TOT (0x73) - ignore it.
-            return;
-        }
-        let body = &data[psi::SectionCommonHeader::SIZE..];
-        if body.len() < 5 {
-            log::warn!("TDT section too short: {} bytes (need 5)", body.len());
-            return;
+        match header.table_id {
+            TDT_TABLE_ID => {
+                let body = &data[psi::SectionCommonHeader::SIZE..];
+                if body.len() < 5 {
+                    log::warn!("TDT section too short: {} bytes (need 5)", body.len());
+                    return;
+                }
+                let section = TdtSection { data: &body[..5] };
+                self.consumer.tdt(ctx, &section);
+            }
+            TOT_TABLE_ID => {
+                let body = &data[psi::SectionCommonHeader::SIZE..];
+                if body.len() < 7 + 4 {
+                    log::warn!("TOT section too short: {} bytes (need 11)", body.len());
+                    return;
+                }
+                if mpeg2ts_reader::mpegts_crc::sum32(data) != 0 {
+                    log::warn!("TOT section CRC check failed");
+                    return;
+                }
+                let body = &body[..body.len() - 4]; // remove CRC bytes
+                let descriptors_loop_length =
+                    usize::from(body[5] & 0b1111) << 8 | usize::from(body[6]);
+                if 7 + descriptors_loop_length > body.len() {
+                    log::warn!(
+                        "TOT descriptors_loop_length {} exceeds section size {}",
+                        descriptors_loop_length,
+                        body.len()
+                    );
+                    return;
+                }
+                let section = TotSection { data: body };
+                self.consumer.tot(ctx, &section);
+            }
+            // PID 0x14 may also carry e.g. stuffing tables - ignore them.
+            _ => (),
         }
-        let section = TdtSection { data: &body[..5] };
-        self.consumer.tdt(ctx, &section);
     }
 }
 
@@ -160,6 +217,56 @@ mod test {
         assert!(processor.consumer.called);
     }
 
+    struct TotAssertConsumer {
+        called: bool,
+    }
+    impl TdtConsumer<NullDemuxContext> for TotAssertConsumer {
+        fn tdt(&mut self, _ctx: &mut NullDemuxContext, _section: &TdtSection<'_>) {
+            panic!("unexpected TDT");
+        }
+        fn tot(&mut self, _ctx: &mut NullDemuxContext, section: &TotSection<'_>) {
+            let ts = section.utc_time().unwrap();
+            assert_eq!(750515445, ts.to_unix_timestamp());
+            let descs: Vec<_> = section
+                .descriptors::<crate::En300_468Descriptors<'_>>()
+                .collect();
+            assert_eq!(1, descs.len());
+            match &descs[0] {
+                Ok(crate::En300_468Descriptors::LocalTimeOffset(d)) => {
+                    assert_eq!(Some(60), d.offset_at("GBR", 0, ts.to_unix_timestamp()));
+                }
+                other => panic!("unexpected descriptor {:?}", other),
+            }
+            self.called = true;
+        }
+    }
+
+    #[test]
+    fn tot_section_parse() {
+        let mut ctx = NullDemuxContext::new();
+        let mut processor = TdtProcessor::new(TotAssertConsumer { called: false });
+
+        #[rustfmt::skip]
+        let mut section = vec![
+            0x73, 0x70, 0x1A, // common header, section_length=26
+            0xC0, 0x79, 0x12, 0x30, 0x45, // 5-byte MJD+BCD timestamp
+            0xF0, 0x0F, // descriptors_loop_length=15
+            0x58, 0x0D,
+            b'G', b'B', b'R', 0x02, 0x01, 0x00, 0xC0, 0xEA, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let crc = mpeg2ts_reader::mpegts_crc::sum32(&section);
+        section.extend_from_slice(&crc.to_be_bytes());
+
+        let header = psi::SectionCommonHeader::new(&section[..psi::SectionCommonHeader::SIZE]);
+        psi::WholeCompactSyntaxPayloadParser::section(
+            &mut processor,
+            &mut ctx,
+            &header,
+            &section[..],
+        );
+        assert!(processor.consumer.called);
+    }
+
     #[test]
     fn tdt_ignores_tot() {
         let mut ctx = NullDemuxContext::new();
@@ -0,0 +1,381 @@
+//! _Teletext_ (tag 0x56), _VBI Teletext_ (tag 0x46) and _VBI Data_ (tag 0x45) descriptors
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+
+/// The kind of Teletext page signalled by a [`TeletextEntry`], as defined in _ETSI EN 300 468_
+/// table 100.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TeletextType {
+    Reserved(u8),
+    InitialPage,
+    SubtitlePage,
+    AdditionalInformationPage,
+    ProgrammeSchedulePage,
+    HearingImpairedSubtitlePage,
+}
+impl TeletextType {
+    pub fn from_id(id: u8) -> TeletextType {
+        match id {
+            0x01 => TeletextType::InitialPage,
+            0x02 => TeletextType::SubtitlePage,
+            0x03 => TeletextType::AdditionalInformationPage,
+            0x04 => TeletextType::ProgrammeSchedulePage,
+            0x05 => TeletextType::HearingImpairedSubtitlePage,
+            _ => TeletextType::Reserved(id),
+        }
+    }
+
+    /// `true` for both normal and hearing-impaired subtitle pages.
+    pub fn is_subtitle(&self) -> bool {
+        matches!(
+            self,
+            TeletextType::SubtitlePage | TeletextType::HearingImpairedSubtitlePage
+        )
+    }
+}
+
+/// A single 5-byte entry from a [`TeletextDescriptor`] or [`VbiTeletextDescriptor`].
+pub struct TeletextEntry<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TeletextEntry<'buf> {
+    const SIZE: usize = 5;
+
+    /// Three-character ISO 639-2 language code
+    pub fn language_code(&self) -> &'buf [u8] {
+        &self.data[0..3]
+    }
+
+    /// Three-character ISO 639-2 language code as a string, or `None` if the bytes are not valid
+    /// UTF-8
+    pub fn language_code_str(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.language_code()).ok()
+    }
+
+    pub fn teletext_type(&self) -> TeletextType {
+        TeletextType::from_id(self.data[3] >> 3)
+    }
+
+    /// The raw 3-bit `teletext_magazine_number` field, where the value `0` stands for magazine 8.
+    pub fn teletext_magazine_number(&self) -> u8 {
+        self.data[3] & 0b111
+    }
+
+    /// The raw `teletext_page_number` field, holding the page's tens and units as two 4-bit
+    /// values.
+    pub fn teletext_page_number(&self) -> u8 {
+        self.data[4]
+    }
+
+    /// The magazine number in the range `1..=8`, as it would be presented to a viewer.
+    pub fn magazine(&self) -> u8 {
+        match self.teletext_magazine_number() {
+            0 => 8,
+            m => m,
+        }
+    }
+
+    /// The magazine and page number combined into the familiar three-digit page number (e.g.
+    /// `888`), or `None` if the page number has tens or units outside `0..=9` (such pages are
+    /// not directly selectable by a viewer).
+    pub fn page(&self) -> Option<u16> {
+        let tens = self.teletext_page_number() >> 4;
+        let units = self.teletext_page_number() & 0x0f;
+        if tens > 9 || units > 9 {
+            None
+        } else {
+            Some(u16::from(self.magazine()) * 100 + u16::from(tens) * 10 + u16::from(units))
+        }
+    }
+}
+impl<'buf> fmt::Debug for TeletextEntry<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TeletextEntry")
+            .field("language_code", &self.language_code_str())
+            .field("teletext_type", &self.teletext_type())
+            .field("magazine", &self.magazine())
+            .field("teletext_page_number", &self.teletext_page_number())
+            .field("page", &self.page())
+            .finish()
+    }
+}
+
+fn teletext_entries(data: &[u8]) -> impl Iterator<Item = TeletextEntry<'_>> {
+    data.chunks_exact(TeletextEntry::SIZE)
+        .map(|data| TeletextEntry { data })
+}
+
+/// Identifies Teletext pages carried in an elementary stream, as defined in _ETSI EN 300 468_
+/// section 6.2.43.
+pub struct TeletextDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> TeletextDescriptor<'buf> {
+    pub const TAG: u8 = 0x56;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<TeletextDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(TeletextDescriptor { data })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = TeletextEntry<'buf>> {
+        teletext_entries(self.data)
+    }
+}
+impl<'buf> fmt::Debug for TeletextDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TeletextDescriptor")
+            .field("entries", &self.entries().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Identifies Teletext pages inserted into the VBI by the receiver, as defined in
+/// _ETSI EN 300 468_ section 6.2.48.  The syntax is identical to [`TeletextDescriptor`].
+pub struct VbiTeletextDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> VbiTeletextDescriptor<'buf> {
+    pub const TAG: u8 = 0x46;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<VbiTeletextDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        Ok(VbiTeletextDescriptor { data })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = TeletextEntry<'buf>> {
+        teletext_entries(self.data)
+    }
+}
+impl<'buf> fmt::Debug for VbiTeletextDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("VbiTeletextDescriptor")
+            .field("entries", &self.entries().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The kind of VBI data carried, as defined in _ETSI EN 300 468_ table 99.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DataServiceId {
+    Reserved(u8),
+    EbuTeletext,
+    InvertedTeletext,
+    Vps,
+    Wss,
+    ClosedCaptioning,
+    Monochrome422Samples,
+}
+impl DataServiceId {
+    pub fn from_id(id: u8) -> DataServiceId {
+        match id {
+            0x01 => DataServiceId::EbuTeletext,
+            0x02 => DataServiceId::InvertedTeletext,
+            0x04 => DataServiceId::Vps,
+            0x05 => DataServiceId::Wss,
+            0x06 => DataServiceId::ClosedCaptioning,
+            0x07 => DataServiceId::Monochrome422Samples,
+            _ => DataServiceId::Reserved(id),
+        }
+    }
+
+    /// `true` for the data services whose descriptor bytes list the VBI lines used.
+    fn has_line_info(&self) -> bool {
+        !matches!(self, DataServiceId::Reserved(_))
+    }
+}
+
+/// A VBI line on which a data service is carried.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VbiLine {
+    /// `true` for the first (odd) field of a frame, `false` for the second (even) field.
+    pub field_parity: bool,
+    /// Line number within the field, or `0` if the line is not specified.
+    pub line_offset: u8,
+}
+
+/// One data service from a [`VbiDataDescriptor`].
+pub struct VbiDataService<'buf> {
+    data_service_id: u8,
+    data: &'buf [u8],
+}
+impl<'buf> VbiDataService<'buf> {
+    pub fn data_service_id(&self) -> DataServiceId {
+        DataServiceId::from_id(self.data_service_id)
+    }
+
+    /// The VBI lines used by this data service.  Empty for reserved `data_service_id` values,
+    /// whose descriptor bytes have no defined meaning.
+    pub fn lines(&self) -> impl Iterator<Item = VbiLine> + 'buf {
+        let data = if self.data_service_id().has_line_info() {
+            self.data
+        } else {
+            &[]
+        };
+        data.iter().map(|b| VbiLine {
+            field_parity: b & 0b0010_0000 != 0,
+            line_offset: b & 0b0001_1111,
+        })
+    }
+}
+impl<'buf> fmt::Debug for VbiDataService<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("VbiDataService")
+            .field("data_service_id", &self.data_service_id())
+            .field("lines", &self.lines().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+struct VbiDataServiceIterator<'buf> {
+    remaining_data: &'buf [u8],
+}
+impl<'buf> Iterator for VbiDataServiceIterator<'buf> {
+    type Item = VbiDataService<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_data.len() < 2 {
+            return None;
+        }
+        let data_service_id = self.remaining_data[0];
+        let end = 2 + usize::from(self.remaining_data[1]);
+        let data = &self.remaining_data[2..end];
+        self.remaining_data = &self.remaining_data[end..];
+        Some(VbiDataService {
+            data_service_id,
+            data,
+        })
+    }
+}
+
+/// Lists the data services and VBI lines that a VBI data stream carries, as defined in
+/// _ETSI EN 300 468_ section 6.2.47.
+pub struct VbiDataDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> VbiDataDescriptor<'buf> {
+    pub const TAG: u8 = 0x45;
+
+    pub fn new(
+        tag: u8,
+        data: &'buf [u8],
+    ) -> Result<VbiDataDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag, Self::TAG);
+        let mut pos = 0;
+        while pos < data.len() {
+            let expected = if pos + 2 > data.len() {
+                pos + 2
+            } else {
+                pos + 2 + usize::from(data[pos + 1])
+            };
+            if expected > data.len() {
+                return Err(descriptor::DescriptorError::NotEnoughData {
+                    tag,
+                    actual: data.len(),
+                    expected,
+                });
+            }
+            pos = expected;
+        }
+        Ok(VbiDataDescriptor { data })
+    }
+
+    pub fn services(&self) -> impl Iterator<Item = VbiDataService<'buf>> {
+        VbiDataServiceIterator {
+            remaining_data: self.data,
+        }
+    }
+}
+impl<'buf> fmt::Debug for VbiDataDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("VbiDataDescriptor")
+            .field("services", &self.services().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn teletext_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            b'e', b'n', b'g',
+            0b0000_1000, 0x88, // initial page, magazine 0 (=8), page 0x88
+            b'e', b'n', b'g',
+            0b0001_0001, 0x01, // subtitle page, magazine 1, page 0x01
+            b'd', b'e', b'u',
+            0b0010_1111, 0x9A, // hearing impaired subtitles, magazine 7, page 0x9A
+        ];
+        let desc = TeletextDescriptor::new(0x56, &data).unwrap();
+        let entries: Vec<_> = desc.entries().collect();
+        assert_eq!(3, entries.len());
+
+        assert_eq!(Some("eng"), entries[0].language_code_str());
+        assert_eq!(TeletextType::InitialPage, entries[0].teletext_type());
+        assert_eq!(8, entries[0].magazine());
+        assert_eq!(Some(888), entries[0].page());
+
+        assert_eq!(TeletextType::SubtitlePage, entries[1].teletext_type());
+        assert_eq!(Some(101), entries[1].page());
+        assert!(entries[1].teletext_type().is_subtitle());
+
+        assert_eq!(Some("deu"), entries[2].language_code_str());
+        assert_eq!(
+            TeletextType::HearingImpairedSubtitlePage,
+            entries[2].teletext_type()
+        );
+        assert_eq!(7, entries[2].magazine());
+        assert_eq!(None, entries[2].page());
+    }
+
+    #[test]
+    fn vbi_data_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            0x01, 0x02, 0b0010_0111, 0b0000_1000, // EBU teletext on lines 7 (field 1) and 8 (field 2)
+            0x10, 0x01, 0xff, // reserved data_service_id
+        ];
+        let desc = VbiDataDescriptor::new(0x45, &data).unwrap();
+        let services: Vec<_> = desc.services().collect();
+        assert_eq!(2, services.len());
+        assert_eq!(DataServiceId::EbuTeletext, services[0].data_service_id());
+        let lines: Vec<_> = services[0].lines().collect();
+        assert_eq!(
+            vec![
+                VbiLine {
+                    field_parity: true,
+                    line_offset: 7
+                },
+                VbiLine {
+                    field_parity: false,
+                    line_offset: 8
+                },
+            ],
+            lines
+        );
+        assert_eq!(DataServiceId::Reserved(0x10), services[1].data_service_id());
+        assert_eq!(0, services[1].lines().count());
+    }
+
+    #[test]
+    fn vbi_data_descriptor_truncated() {
+        let data = [0x01, 0x03, 0x07];
+        assert!(matches!(
+            VbiDataDescriptor::new(0x45, &data),
+            Err(descriptor::DescriptorError::NotEnoughData {
+                tag: 0x45,
+                actual: 3,
+                expected: 5
+            })
+        ));
+    }
+}
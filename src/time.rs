@@ -35,6 +35,27 @@ fn is_valid_bcd(byte: u8) -> bool {
     (byte >> 4) <= 9 && (byte & 0x0f) <= 9
 }
 
+/// Calendar date (year, month, day) of the given Modified Julian Date, using the EN 300 468
+/// Annex C algorithm.
+pub(crate) fn date_from_mjd(mjd: i64) -> (i32, i32, i32) {
+    let y_prime = ((mjd as f64 - 15078.2) / 365.25) as i64;
+    let m_prime = ((mjd as f64 - 14956.1 - (y_prime as f64 * 365.25).floor()) / 30.6001) as i64;
+    let d = mjd - 14956 - (y_prime as f64 * 365.25) as i64 - (m_prime as f64 * 30.6001) as i64;
+    let k = if m_prime == 14 || m_prime == 15 { 1 } else { 0 };
+    let y = y_prime + k + 1900;
+    let m = m_prime - 1 - k * 12;
+    (y as i32, m as i32, d as i32)
+}
+
+/// Modified Julian Date of the given calendar date, using the EN 300 468 Annex C algorithm.
+pub(crate) fn mjd_from_date(year: i32, month: i32, day: i32) -> i64 {
+    let l = if month == 1 || month == 2 { 1 } else { 0 };
+    14956
+        + i64::from(day)
+        + ((f64::from(year - 1900 - l)) * 365.25) as i64
+        + ((f64::from(month + 1 + l * 12)) * 30.6001) as i64
+}
+
 /// A DVB timestamp encoded as a 5-byte Modified Julian Date plus BCD-encoded UTC time,
 /// as defined in EN 300 468 Annex C.
 pub struct MjdTimestamp<'buf> {
@@ -74,14 +95,7 @@ impl<'buf> MjdTimestamp<'buf> {
     /// Calendar date (year, month, day) derived from the MJD value using the
     /// EN 300 468 Annex C algorithm.
     pub fn date(&self) -> (i32, i32, i32) {
-        let mjd = i64::from(self.mjd());
-        let y_prime = ((mjd as f64 - 15078.2) / 365.25) as i64;
-        let m_prime = ((mjd as f64 - 14956.1 - (y_prime as f64 * 365.25).floor()) / 30.6001) as i64;
-        let d = mjd - 14956 - (y_prime as f64 * 365.25) as i64 - (m_prime as f64 * 30.6001) as i64;
-        let k = if m_prime == 14 || m_prime == 15 { 1 } else { 0 };
-        let y = y_prime + k + 1900;
-        let m = m_prime - 1 - k * 12;
-        (y as i32, m as i32, d as i32)
+        date_from_mjd(i64::from(self.mjd()))
     }
 
     /// Hours (BCD-decoded)
@@ -219,4 +233,15 @@ mod test {
             Err(MjdTimestampError::SecondsOutOfRange(60))
         ));
     }
+
+    #[test]
+    fn mjd_date_round_trip() {
+        // Example from EN 300 468 Annex C
+        assert_eq!(45218, mjd_from_date(1982, 9, 6));
+        assert_eq!((1982, 9, 6), date_from_mjd(45218));
+        for mjd in [40587, 51543, 51544, 51603, 51604, 60399, 60400] {
+            let (y, m, d) = date_from_mjd(mjd);
+            assert_eq!(mjd, mjd_from_date(y, m, d));
+        }
+    }
 }
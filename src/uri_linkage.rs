@@ -0,0 +1,185 @@
+//! _URI Linkage Descriptor_ (extension tag 0x13)
+//!
+//! Points receivers at resources delivered over IP which complement the broadcast, such as the
+//! online SDT or a DVB-I service list.  The descriptor may be placed in the NIT, BAT or SDT; see
+//! [`uri_linkages()`] for finding those in a descriptor loop.
+use crate::extension::ExtensionDescriptors;
+use crate::En300_468Descriptors;
+use mpeg2ts_reader::descriptor;
+use std::fmt;
+use std::time::Duration;
+
+/// The kind of resource identified by a [`UriLinkageDescriptor`], as given in
+/// _ETSI EN 300 468_ table 135.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UriLinkageType {
+    /// Online SDT (OSDT), as used by CI Plus
+    OnlineSdt,
+    /// DVB-IPTV service discovery and selection (SD&S), as defined in _ETSI TS 102 034_
+    DvbIptvSdAndS,
+    /// Material Resolution Server, for companion screen applications, as defined in
+    /// _ETSI TS 103 286-3_
+    MaterialResolutionServer,
+    /// DVB-I service list, or service list registry, as defined in _ETSI TS 103 770_
+    DvbI,
+    Reserved(u8),
+    UserDefined(u8),
+}
+impl UriLinkageType {
+    pub fn from_id(id: u8) -> UriLinkageType {
+        match id {
+            0x00 => UriLinkageType::OnlineSdt,
+            0x01 => UriLinkageType::DvbIptvSdAndS,
+            0x02 => UriLinkageType::MaterialResolutionServer,
+            0x03 => UriLinkageType::DvbI,
+            0x04..=0x7F => UriLinkageType::Reserved(id),
+            0x80..=0xFF => UriLinkageType::UserDefined(id),
+        }
+    }
+
+    /// `true` for the types where the descriptor gives a `min_polling_interval`
+    fn has_polling_interval(id: u8) -> bool {
+        matches!(id, 0x00 | 0x01)
+    }
+}
+
+/// Gives the URI of a resource related to the network, bouquet or service, as defined in
+/// _ETSI EN 300 468_ section 6.4.15.
+///
+/// This is carried within an [`ExtensionDescriptor`](../extension/struct.ExtensionDescriptor.html).
+pub struct UriLinkageDescriptor<'buf> {
+    data: &'buf [u8],
+}
+impl<'buf> UriLinkageDescriptor<'buf> {
+    pub const TAG_EXTENSION: u8 = 0x13;
+
+    pub fn new(
+        tag_extension: u8,
+        data: &'buf [u8],
+    ) -> Result<UriLinkageDescriptor<'buf>, descriptor::DescriptorError> {
+        assert_eq!(tag_extension, Self::TAG_EXTENSION);
+        if data.len() < 2 {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected: 2,
+            });
+        }
+        let mut expected = 2 + usize::from(data[1]);
+        if UriLinkageType::has_polling_interval(data[0]) {
+            expected += 2;
+        }
+        if data.len() < expected {
+            return Err(descriptor::DescriptorError::NotEnoughData {
+                tag: crate::extension::ExtensionDescriptor::TAG,
+                actual: data.len(),
+                expected,
+            });
+        }
+        Ok(UriLinkageDescriptor { data })
+    }
+
+    pub fn uri_linkage_type(&self) -> UriLinkageType {
+        UriLinkageType::from_id(self.data[0])
+    }
+
+    fn uri_end(&self) -> usize {
+        2 + usize::from(self.data[1])
+    }
+
+    /// The URI as raw bytes
+    pub fn uri_bytes(&self) -> &'buf [u8] {
+        &self.data[2..self.uri_end()]
+    }
+
+    /// The URI, or `None` if the bytes are not valid UTF-8
+    pub fn uri(&self) -> Option<&'buf str> {
+        std::str::from_utf8(self.uri_bytes()).ok()
+    }
+
+    /// The minimum time the receiver should wait between requests for the resource, given for
+    /// the online SDT and DVB-IPTV SD&S types only
+    pub fn min_polling_interval(&self) -> Option<Duration> {
+        if UriLinkageType::has_polling_interval(self.data[0]) {
+            let pos = self.uri_end();
+            let interval = u16::from(self.data[pos]) << 8 | u16::from(self.data[pos + 1]);
+            // signalled in units of 2 seconds
+            Some(Duration::from_secs(u64::from(interval) * 2))
+        } else {
+            None
+        }
+    }
+
+    /// Data whose meaning depends on the `uri_linkage_type` (for example, the
+    /// `dvb_i_private_data()` structure of the DVB-I type)
+    pub fn private_data(&self) -> &'buf [u8] {
+        if UriLinkageType::has_polling_interval(self.data[0]) {
+            &self.data[self.uri_end() + 2..]
+        } else {
+            &self.data[self.uri_end()..]
+        }
+    }
+}
+impl<'buf> fmt::Debug for UriLinkageDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("UriLinkageDescriptor")
+            .field("uri_linkage_type", &self.uri_linkage_type())
+            .field("uri", &self.uri())
+            .field("min_polling_interval", &self.min_polling_interval())
+            .field("private_data", &self.private_data())
+            .finish()
+    }
+}
+
+/// Finds the URI linkage descriptors within the given descriptor loop (e.g. that of the NIT or
+/// a service in the SDT), skipping any which cannot be decoded.
+pub fn uri_linkages<'buf>(
+    descs: impl IntoIterator<Item = Result<En300_468Descriptors<'buf>, descriptor::DescriptorError>>,
+) -> impl Iterator<Item = UriLinkageDescriptor<'buf>> {
+    descs.into_iter().filter_map(|d| match d {
+        Ok(En300_468Descriptors::Extension(ext)) => match ext.descriptor() {
+            Ok(ExtensionDescriptors::URILinkage(d)) => Some(d),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uri_linkage_descriptor() {
+        #[rustfmt::skip]
+        let data = [
+            0x00, 0x0B, b'h', b't', b't', b'p', b':', b'/', b'/', b'o', b's', b'd', b't',
+            0x00, 0x1E, // 60 seconds
+            0xAA,
+        ];
+        let desc = UriLinkageDescriptor::new(0x13, &data).unwrap();
+        assert_eq!(UriLinkageType::OnlineSdt, desc.uri_linkage_type());
+        assert_eq!(Some("http://osdt"), desc.uri());
+        assert_eq!(Some(Duration::from_secs(60)), desc.min_polling_interval());
+        assert_eq!(&[0xAA], desc.private_data());
+        assert!(UriLinkageDescriptor::new(0x13, &data[..14]).is_err());
+        assert!(UriLinkageDescriptor::new(0x13, &data[..1]).is_err());
+    }
+
+    #[test]
+    fn find_uri_linkages() {
+        #[rustfmt::skip]
+        let data = [
+            // network_name_descriptor
+            0x40, 0x01, b'N',
+            // uri_linkage_descriptor, DVB-I
+            0x7F, 0x07, 0x13, 0x03, 0x03, b'a', b'/', b'b', 0x01,
+        ];
+        let links: Vec<_> = uri_linkages(descriptor::DescriptorIter::new(&data[..])).collect();
+        assert_eq!(1, links.len());
+        assert_eq!(UriLinkageType::DvbI, links[0].uri_linkage_type());
+        assert_eq!(Some("a/b"), links[0].uri());
+        assert_eq!(None, links[0].min_polling_interval());
+        assert_eq!(&[0x01], links[0].private_data());
+    }
+}